@@ -0,0 +1,118 @@
+use crate::*;
+
+/// The path under which Bluez exposes the local adapters, such as `/org/bluez/hci0`.
+pub(crate) const ADAPTER_ROOT: &'static str = "/org/bluez";
+
+/// A snapshot of the properties of a local `org.bluez.Adapter1` object.
+///
+/// Returned by [`Bluetooth::adapters()`] and by [`AdapterEvent::Added`].
+/// Unlike the state cached by [`Bluetooth`], the values are not kept up to date.
+///
+/// [`Bluetooth::adapters()`]: ./struct.Bluetooth.html#method.adapters
+/// [`Bluetooth`]: ./struct.Bluetooth.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AdapterInfo {
+    /// The DBus path of the adapter, such as `/org/bluez/hci0`.
+    pub path: PathBuf,
+    pub addr: MAC,
+    pub addr_type: AddrType,
+    pub name: String,
+    pub alias: String,
+    pub class: u32,
+    pub powered: bool,
+    pub discoverable: bool,
+    pub discoverable_timeout: u32,
+    pub pairable: bool,
+    pub pairable_timeout: u32,
+    pub discovering: bool,
+    pub uuids: Vec<UUID>,
+    pub modalias: Option<String>,
+    pub roles: Vec<String>,
+}
+impl AdapterInfo {
+    pub(crate) fn from_props(
+        mut props: HashMap<String, Variant>,
+        path: PathBuf,
+    ) -> Result<Self, Error> {
+        let addr: String = match props.remove("Address") {
+            Some(var) => var.get()?,
+            None => {
+                return Err(Error::DbusReqErr(
+                    "No 'Address' property was present on adapter!".to_string(),
+                ))
+            }
+        };
+        if !validate_mac(&addr) {
+            return Err(Error::DbusReqErr(
+                "'Address' property was in invalid format!".to_string(),
+            ));
+        }
+        let addr_type = match props.remove("AddressType") {
+            Some(var) => {
+                let addr_type: String = var.get()?;
+                AddrType::from_str(&addr_type).ok_or_else(|| {
+                    Error::DbusReqErr(format!(
+                        "Invalid adapter returned; unknown AddressType: {}",
+                        addr_type
+                    ))
+                })?
+            }
+            None => AddrType::Public,
+        };
+        let name: String = match props.remove("Name") {
+            Some(var) => var.get()?,
+            None => String::new(),
+        };
+        let alias = match props.remove("Alias") {
+            Some(var) => var.get()?,
+            None => name.clone(),
+        };
+        let uuids: Vec<String> = match props.remove("UUIDs") {
+            Some(var) => var.get()?,
+            None => Vec::new(),
+        };
+        Ok(AdapterInfo {
+            path,
+            addr: addr.to_mac(),
+            addr_type,
+            name,
+            alias,
+            class: get_or_default(&mut props, "Class")?,
+            powered: get_or_default(&mut props, "Powered")?,
+            discoverable: get_or_default(&mut props, "Discoverable")?,
+            discoverable_timeout: get_or_default(&mut props, "DiscoverableTimeout")?,
+            pairable: get_or_default(&mut props, "Pairable")?,
+            pairable_timeout: get_or_default(&mut props, "PairableTimeout")?,
+            discovering: get_or_default(&mut props, "Discovering")?,
+            uuids: uuids.into_iter().map(|uuid| uuid.to_uuid()).collect(),
+            modalias: match props.remove("Modalias") {
+                Some(var) => Some(var.get()?),
+                None => None,
+            },
+            roles: get_or_default(&mut props, "Roles")?,
+        })
+    }
+}
+fn get_or_default<'r, 'buf: 'r, T: Unmarshal<'r, 'buf> + Default>(
+    props: &mut HashMap<String, Variant<'buf>>,
+    prop: &str,
+) -> Result<T, Error> {
+    match props.remove(prop) {
+        Some(var) => Ok(var.get()?),
+        None => Ok(T::default()),
+    }
+}
+
+/// Events for local adapters being added or removed from the system,
+/// such as when a USB dongle is plugged in or unplugged.
+///
+/// These are queued as [`BluetoothEvent::Adapter`] while [`Bluetooth::process_requests()`] is called.
+///
+/// [`BluetoothEvent::Adapter`]: ./enum.BluetoothEvent.html#variant.Adapter
+/// [`Bluetooth::process_requests()`]: ./struct.Bluetooth.html#method.process_requests
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AdapterEvent {
+    Added(AdapterInfo),
+    /// Contains the DBus path of the removed adapter.
+    Removed(PathBuf),
+}
@@ -1,10 +1,29 @@
 use crate::Error;
 use rustbus::{message_builder::MarshalledMessage, MessageType};
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 pub(crate) const POWER: &'static str = "Setting power";
 pub(crate) const DISCOVERABLE: &'static str = "Setting discoverable";
+pub(crate) const DISCOVERY_FILTER: &'static str = "SetDiscoveryFilter";
+pub(crate) const START_DISCOVERY: &'static str = "StartDiscovery";
+pub(crate) const STOP_DISCOVERY: &'static str = "StopDiscovery";
+pub(crate) const CONNECT: &'static str = "Connect";
+pub(crate) const DISCONNECT: &'static str = "Disconnect";
+pub(crate) const CONNECT_PROFILE: &'static str = "ConnectProfile";
+pub(crate) const DISCONNECT_PROFILE: &'static str = "DisconnectProfile";
+pub(crate) const PAIR: &'static str = "Pair";
+pub(crate) const CANCEL_PAIRING: &'static str = "CancelPairing";
+pub(crate) const REMOVE_DEVICE: &'static str = "RemoveDevice";
+pub(crate) const START_NOTIFY: &'static str = "StartNotify";
+pub(crate) const STOP_NOTIFY: &'static str = "StopNotify";
+pub(crate) const TRUSTED: &'static str = "Setting trusted";
+pub(crate) const BLOCKED: &'static str = "Setting blocked";
+pub(crate) const WAKE_ALLOWED: &'static str = "Setting wake allowed";
+pub(crate) const ALIAS: &'static str = "Setting alias";
+pub(crate) const PAIRABLE: &'static str = "Setting pairable";
+pub(crate) const PAIRABLE_TIMEOUT: &'static str = "Setting pairable timeout";
+pub(crate) const DISCOVERABLE_TIMEOUT: &'static str = "Setting discoverable timeout";
 pub(crate) fn set_power_cb(
     res: MarshalledMessage,
     (powered, on, err_str): (Rc<Cell<bool>>, bool, &'static str),
@@ -21,3 +40,48 @@ pub(crate) fn set_power_cb(
         _ => unreachable!(),
     }
 }
+pub(crate) fn set_timeout_cb(
+    res: MarshalledMessage,
+    (timeout, val, err_str): (Rc<Cell<u32>>, u32, &'static str),
+) -> Result<(), Error> {
+    match res.typ {
+        MessageType::Reply => {
+            timeout.replace(val);
+            Ok(())
+        }
+        MessageType::Error => Err(Error::DbusReqErr(format!(
+            "{} call failed: {:?}",
+            err_str, res
+        ))),
+        _ => unreachable!(),
+    }
+}
+pub(crate) fn set_alias_cb(
+    res: MarshalledMessage,
+    (alias, val, err_str): (Rc<RefCell<String>>, String, &'static str),
+) -> Result<(), Error> {
+    match res.typ {
+        MessageType::Reply => {
+            // an empty alias resets it to the default, which Bluez will report with PropertiesChanged
+            if !val.is_empty() {
+                alias.replace(val);
+            }
+            Ok(())
+        }
+        MessageType::Error => Err(Error::DbusReqErr(format!(
+            "{} call failed: {:?}",
+            err_str, res
+        ))),
+        _ => unreachable!(),
+    }
+}
+pub(crate) fn method_cb(res: MarshalledMessage, err_str: &'static str) -> Result<(), Error> {
+    match res.typ {
+        MessageType::Reply => Ok(()),
+        MessageType::Error => Err(Error::DbusReqErr(format!(
+            "{} call failed: {:?}",
+            err_str, res
+        ))),
+        _ => unreachable!(),
+    }
+}
@@ -1,14 +1,39 @@
 use crate::gatt::*;
-use crate::{Bluetooth, Error, ToMAC, ToUUID, Variant, MAC, UUID};
-use std::cell::Cell;
-use std::collections::HashMap;
+use crate::interfaces::*;
+use crate::{bluetooth_cb, Pending, PendingType};
+use crate::{Bluetooth, BluetoothEvent, Error, ResolveError, ToMAC, ToUUID, Variant, MAC, UUID};
+use nix::poll;
+use rustbus::client_conn::Timeout;
+use rustbus::message_builder::{MarshalledMessage, MessageBuilder};
+use rustbus::wire::marshal::traits::{Marshal, Signature};
+use rustbus::wire::unmarshal::traits::Unmarshal;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::os::unix::io::AsRawFd;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AddrType {
     Public,
     Random,
 }
+impl AddrType {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            AddrType::Public => "public",
+            AddrType::Random => "random",
+        }
+    }
+    pub(crate) fn from_str(addr_type: &str) -> Option<Self> {
+        match addr_type {
+            "public" => Some(AddrType::Public),
+            "random" => Some(AddrType::Random),
+            _ => None,
+        }
+    }
+}
 pub trait Device<'a>: HasChildren<'a> {
     type ServiceType: Service<'a>;
     fn services(&mut self) -> Vec<UUID>;
@@ -24,8 +49,69 @@ pub(crate) struct RemoteDeviceBase {
     pub(crate) services: HashMap<MAC, RemoteServiceBase>,
     connected: Rc<Cell<bool>>,
     paired: Rc<Cell<bool>>,
+    name: Option<String>,
+    alias: Rc<RefCell<String>>,
+    addr_type: AddrType,
+    rssi: Option<i16>,
+    tx_power: Option<i16>,
+    appearance: Option<u16>,
+    icon: Option<String>,
+    class: Option<u32>,
+    uuids: Vec<UUID>,
+    manu_data: HashMap<u16, Vec<u8>>,
+    serv_data: HashMap<UUID, Vec<u8>>,
+    services_resolved: bool,
+    trusted: Rc<Cell<bool>>,
+    blocked: Rc<Cell<bool>>,
+    wake_allowed: Rc<Cell<bool>>,
+    legacy_pairing: bool,
+    bonded: bool,
+    modalias: Option<String>,
     //comp_map: HashMap<OsString, MAC>,
 }
+/// Removes `prop` from `props` and gets its value, if it is present.
+fn take_prop<'r, 'buf: 'r, T: Unmarshal<'r, 'buf>>(
+    props: &mut HashMap<String, Variant<'buf>>,
+    prop: &str,
+) -> Result<Option<T>, Error> {
+    match props.remove(prop) {
+        Some(var) => match var.get::<T>() {
+            Ok(val) => Ok(Some(val)),
+            Err(_) => Err(Error::DbusReqErr(format!(
+                "Invalid device returned; {} field is invalid type",
+                prop
+            ))),
+        },
+        None => Ok(None),
+    }
+}
+fn parse_addr_type(addr_type: String) -> Result<AddrType, Error> {
+    AddrType::from_str(&addr_type).ok_or_else(|| {
+        Error::DbusReqErr(format!(
+            "Invalid device returned; unknown AddressType: {}",
+            addr_type
+        ))
+    })
+}
+fn parse_uuids(uuids: Vec<String>) -> Vec<UUID> {
+    uuids.into_iter().map(|uuid| uuid.to_uuid()).collect()
+}
+fn parse_manu_data(var: &Variant) -> Result<HashMap<u16, Vec<u8>>, Error> {
+    let data: HashMap<u16, Variant> = var.get()?;
+    let mut ret = HashMap::with_capacity(data.len());
+    for (id, bytes) in data {
+        ret.insert(id, bytes.get()?);
+    }
+    Ok(ret)
+}
+fn parse_serv_data(var: &Variant) -> Result<HashMap<UUID, Vec<u8>>, Error> {
+    let data: HashMap<String, Variant> = var.get()?;
+    let mut ret = HashMap::with_capacity(data.len());
+    for (uuid, bytes) in data {
+        ret.insert(uuid.to_uuid(), bytes.get()?);
+    }
+    Ok(ret)
+}
 impl RemoteDeviceBase {
     pub(crate) fn from_props(
         mut value: HashMap<String, Variant>,
@@ -76,27 +162,139 @@ impl RemoteDeviceBase {
                 ))
             }
         };
+        let addr_type = match take_prop(&mut value, "AddressType")? {
+            Some(addr_type) => parse_addr_type(addr_type)?,
+            None => AddrType::Public,
+        };
+        let manu_data = match value.remove("ManufacturerData") {
+            Some(var) => parse_manu_data(&var)?,
+            None => HashMap::new(),
+        };
+        let serv_data = match value.remove("ServiceData") {
+            Some(var) => parse_serv_data(&var)?,
+            None => HashMap::new(),
+        };
+        let name = take_prop(&mut value, "Name")?;
+        // Bluez falls back to the name or address if the alias isn't set
+        let alias = match take_prop(&mut value, "Alias")? {
+            Some(alias) => alias,
+            None => name.clone().unwrap_or_else(|| mac.to_string()),
+        };
         Ok(RemoteDeviceBase {
             mac,
             path,
             connected,
             paired,
+            name,
+            alias: Rc::new(RefCell::new(alias)),
+            addr_type,
+            rssi: take_prop(&mut value, "RSSI")?,
+            tx_power: take_prop(&mut value, "TxPower")?,
+            appearance: take_prop(&mut value, "Appearance")?,
+            icon: take_prop(&mut value, "Icon")?,
+            class: take_prop(&mut value, "Class")?,
+            uuids: parse_uuids(take_prop(&mut value, "UUIDs")?.unwrap_or_default()),
+            manu_data,
+            serv_data,
+            services_resolved: take_prop(&mut value, "ServicesResolved")?.unwrap_or(false),
+            trusted: Rc::new(Cell::new(
+                take_prop(&mut value, "Trusted")?.unwrap_or(false),
+            )),
+            blocked: Rc::new(Cell::new(
+                take_prop(&mut value, "Blocked")?.unwrap_or(false),
+            )),
+            wake_allowed: Rc::new(Cell::new(
+                take_prop(&mut value, "WakeAllowed")?.unwrap_or(false),
+            )),
+            legacy_pairing: take_prop(&mut value, "LegacyPairing")?.unwrap_or(false),
+            bonded: take_prop(&mut value, "Bonded")?.unwrap_or(false),
+            modalias: take_prop(&mut value, "Modalias")?,
             services: HashMap::new(),
         })
     }
+    /// Updates the device from changed properties, queuing events for the changes
+    /// applications are interested in. Events are only queued for values that differ
+    /// from the stored ones.
     pub(crate) fn update_from_changed(
         &mut self,
         changed: HashMap<String, Variant>,
+        events: &mut VecDeque<BluetoothEvent>,
     ) -> Result<(), Error> {
         for (prop, var) in changed {
             match prop.as_str() {
-                "Connected" => self.connected.set(var.get()?),
-                "Paired" => self.paired.set(var.get()?),
+                "Connected" => {
+                    let connected = var.get()?;
+                    if self.connected.replace(connected) == connected {
+                        continue;
+                    }
+                    events.push_back(if connected {
+                        BluetoothEvent::Connected(self.mac.clone())
+                    } else {
+                        BluetoothEvent::Disconnected(self.mac.clone())
+                    });
+                }
+                "Paired" => {
+                    let paired = var.get()?;
+                    if self.paired.replace(paired) == paired {
+                        continue;
+                    }
+                    events.push_back(BluetoothEvent::PairedChanged(self.mac.clone(), paired));
+                }
+                "Name" => self.name = Some(var.get()?),
+                "Alias" => {
+                    self.alias.replace(var.get()?);
+                }
+                "AddressType" => self.addr_type = parse_addr_type(var.get()?)?,
+                "RSSI" => {
+                    let rssi = var.get()?;
+                    if self.rssi.replace(rssi) == Some(rssi) {
+                        continue;
+                    }
+                    events.push_back(BluetoothEvent::RssiChanged(self.mac.clone(), rssi));
+                }
+                "TxPower" => self.tx_power = Some(var.get()?),
+                "Appearance" => self.appearance = Some(var.get()?),
+                "Icon" => self.icon = Some(var.get()?),
+                "Class" => self.class = Some(var.get()?),
+                "UUIDs" => self.uuids = parse_uuids(var.get()?),
+                "ManufacturerData" => self.manu_data = parse_manu_data(&var)?,
+                "ServiceData" => self.serv_data = parse_serv_data(&var)?,
+                "ServicesResolved" => {
+                    let resolved = var.get()?;
+                    if resolved && !self.services_resolved {
+                        events.push_back(BluetoothEvent::ServicesResolved(self.mac.clone()));
+                    }
+                    self.services_resolved = resolved;
+                }
+                "Trusted" => self.trusted.set(var.get()?),
+                "Blocked" => self.blocked.set(var.get()?),
+                "WakeAllowed" => self.wake_allowed.set(var.get()?),
+                "LegacyPairing" => self.legacy_pairing = var.get()?,
+                "Bonded" => self.bonded = var.get()?,
+                "Modalias" => self.modalias = Some(var.get()?),
                 _ => (),
             }
         }
         Ok(())
     }
+    /// Clears the optional properties listed as invalidated in a `PropertiesChanged` signal.
+    pub(crate) fn invalidate(&mut self, invalidated: Vec<String>) {
+        for prop in invalidated {
+            match prop.as_str() {
+                "Name" => self.name = None,
+                "RSSI" => self.rssi = None,
+                "TxPower" => self.tx_power = None,
+                "Appearance" => self.appearance = None,
+                "Icon" => self.icon = None,
+                "Class" => self.class = None,
+                "UUIDs" => self.uuids.clear(),
+                "ManufacturerData" => self.manu_data.clear(),
+                "ServiceData" => self.serv_data.clear(),
+                "Modalias" => self.modalias = None,
+                _ => (),
+            }
+        }
+    }
     pub(crate) fn match_dev(
         &mut self,
         path: &Path,
@@ -107,7 +305,7 @@ impl RemoteDeviceBase {
                     Some(None)
                 } else {
                     let r_str = remaining.to_str().unwrap();
-                    if &r_str[..4] != "serv" {
+                    if !r_str.starts_with("serv") {
                         return None;
                     }
                     for uuid in self.get_children() {
@@ -164,19 +362,355 @@ impl RemoteDevice<'_> {
         }
         self.blue.devices.get_mut(&self.mac).unwrap()
     }
+    fn device_call<U: 'static>(
+        &mut self,
+        method: &str,
+        profile: Option<&UUID>,
+        data: U,
+        cb: &'static dyn Fn(MarshalledMessage, U) -> Result<(), Error>,
+    ) -> Result<Pending<Result<(), Error>, U>, Error> {
+        let mut msg = MessageBuilder::new()
+            .call(method.to_string())
+            .on(self.get_base().path.to_str().unwrap().to_string())
+            .with_interface(DEV_IF_STR.to_string())
+            .at(BLUEZ_DEST.to_string())
+            .build();
+        if let Some(uuid) = profile {
+            msg.body.push_param(uuid.to_string()).unwrap();
+        }
+        let dbus_res = self.blue.rpc_con.send_message(&mut msg, Timeout::Infinite)?;
+        Ok(Pending {
+            typ: Some(PendingType::MessageCb(cb)),
+            dbus_res,
+            data: Some(data),
+            leaking: Rc::downgrade(&self.blue.leaking),
+        })
+    }
+    fn set_prop<T: Marshal + Signature, U: 'static>(
+        &mut self,
+        prop: &str,
+        val: T,
+        data: U,
+        cb: &'static dyn Fn(MarshalledMessage, U) -> Result<(), Error>,
+    ) -> Result<Pending<Result<(), Error>, U>, Error> {
+        let mut msg = MessageBuilder::new()
+            .call("Set".to_string())
+            .on(self.get_base().path.to_str().unwrap().to_string())
+            .with_interface(PROP_IF_STR.to_string())
+            .at(BLUEZ_DEST.to_string())
+            .build();
+        msg.body.push_param2(DEV_IF_STR, prop).unwrap();
+        msg.body.push_variant(val).unwrap();
+        let dbus_res = self.blue.rpc_con.send_message(&mut msg, Timeout::Infinite)?;
+        Ok(Pending {
+            typ: Some(PendingType::MessageCb(cb)),
+            dbus_res,
+            data: Some(data),
+            leaking: Rc::downgrade(&self.blue.leaking),
+        })
+    }
     #[inline]
     pub fn connected(&self) -> bool {
         self.get_base().connected.get()
     }
-    pub fn connect(&mut self) -> Result<(), Error> {
-        unimplemented!()
+    /// Connect to the remote device, connecting all of the auto-connectable profiles it supports.
+    /// See `Connect` in the [Device API].
+    ///
+    /// [Device API]: https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/device-api.txt
+    pub fn connect(
+        &mut self,
+    ) -> Result<Pending<Result<(), Error>, (Rc<Cell<bool>>, bool, &'static str)>, Error> {
+        let data = (self.get_base().connected.clone(), true, bluetooth_cb::CONNECT);
+        self.device_call("Connect", None, data, &bluetooth_cb::set_power_cb)
+    }
+    /// Connect to the remote device and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn connect_wait(&mut self) -> Result<(), Error> {
+        let pend = self.connect()?;
+        self.blue.wait_result_variant(pend)
+    }
+    /// Connect to the remote device and waits until Bluez has resolved its GATT services.
+    ///
+    /// Bluez publishes all of the device's services, characteristics and descriptors before
+    /// it sets `ServicesResolved`, so when this returns `Ok` they are all available
+    /// through [`get_children()`]. If the services aren't resolved within `timeout`
+    /// `Error::Timeout` is returned, but the connection attempt is not cancelled.
+    ///
+    /// **Calls process_requests()**
+    ///
+    /// [`get_children()`]: ./struct.RemoteDevice.html#method.get_children
+    pub fn connect_and_resolve(&mut self, timeout: Duration) -> Result<(), Error> {
+        let deadline = Instant::now() + timeout;
+        let mut pend = if self.connected() {
+            None
+        } else {
+            Some(self.connect()?)
+        };
+        loop {
+            // handle everything that has already been received before waiting on the socket,
+            // so messages that were read into the connection's buffer aren't left waiting
+            self.blue.process_requests()?;
+            if let Some(p) = pend.take() {
+                match self.blue.try_resolve(p) {
+                    Ok(res) => res?,
+                    Err(ResolveError::StillPending(p)) => pend = Some(p),
+                    Err(ResolveError::Error(_, e)) => return Err(e),
+                }
+            }
+            if !self.blue.devices.contains_key(&self.mac) {
+                return Err(Error::Bluez(format!(
+                    "{} was removed while resolving its services",
+                    self.mac
+                )));
+            }
+            if pend.is_none() && self.services_resolved() {
+                return Ok(());
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::Timeout);
+            }
+            // wait for more messages from Bluez instead of spinning
+            let remaining = (deadline - now).as_millis().min(i32::MAX as u128) as i32;
+            let mut poll_fds = [poll::PollFd::new(
+                self.blue.as_raw_fd(),
+                poll::PollFlags::POLLIN,
+            )];
+            poll::poll(&mut poll_fds, remaining)?;
+        }
+    }
+    /// Disconnect from the remote device, gracefully disconnecting all of its connected profiles.
+    pub fn disconnect(
+        &mut self,
+    ) -> Result<Pending<Result<(), Error>, (Rc<Cell<bool>>, bool, &'static str)>, Error> {
+        let data = (self.get_base().connected.clone(), false, bluetooth_cb::DISCONNECT);
+        self.device_call("Disconnect", None, data, &bluetooth_cb::set_power_cb)
+    }
+    /// Disconnect from the remote device and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn disconnect_wait(&mut self) -> Result<(), Error> {
+        let pend = self.disconnect()?;
+        self.blue.wait_result_variant(pend)
+    }
+    /// Connect a specific profile of the remote device, identified by its `uuid`.
+    pub fn connect_profile(
+        &mut self,
+        uuid: &UUID,
+    ) -> Result<Pending<Result<(), Error>, &'static str>, Error> {
+        self.device_call(
+            "ConnectProfile",
+            Some(uuid),
+            bluetooth_cb::CONNECT_PROFILE,
+            &bluetooth_cb::method_cb,
+        )
+    }
+    /// Connect a specific profile of the remote device and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn connect_profile_wait(&mut self, uuid: &UUID) -> Result<(), Error> {
+        let pend = self.connect_profile(uuid)?;
+        self.blue.wait_result_variant(pend)
+    }
+    /// Disconnect a specific profile of the remote device, identified by its `uuid`.
+    pub fn disconnect_profile(
+        &mut self,
+        uuid: &UUID,
+    ) -> Result<Pending<Result<(), Error>, &'static str>, Error> {
+        self.device_call(
+            "DisconnectProfile",
+            Some(uuid),
+            bluetooth_cb::DISCONNECT_PROFILE,
+            &bluetooth_cb::method_cb,
+        )
+    }
+    /// Disconnect a specific profile of the remote device and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn disconnect_profile_wait(&mut self, uuid: &UUID) -> Result<(), Error> {
+        let pend = self.disconnect_profile(uuid)?;
+        self.blue.wait_result_variant(pend)
     }
     #[inline]
     pub fn paired(&self) -> bool {
         self.get_base().paired.get()
     }
-    pub fn pair(&mut self) -> Result<(), Error> {
-        unimplemented!()
+    /// Get the remote name of the device, if it is known.
+    pub fn remote_name(&self) -> Option<&str> {
+        self.get_base().name.as_deref()
+    }
+    /// Get the alias of the device. Bluez defaults this to the remote name,
+    /// or the address if the name is unknown.
+    pub fn alias(&self) -> String {
+        self.get_base().alias.borrow().clone()
+    }
+    /// Set the alias of the device. Setting an empty alias will restore the default
+    /// alias provided by Bluez.
+    pub fn set_alias(
+        &mut self,
+        alias: &str,
+    ) -> Result<Pending<Result<(), Error>, (Rc<RefCell<String>>, String, &'static str)>, Error>
+    {
+        let data = (
+            self.get_base().alias.clone(),
+            alias.to_string(),
+            bluetooth_cb::ALIAS,
+        );
+        self.set_prop("Alias", alias.to_string(), data, &bluetooth_cb::set_alias_cb)
+    }
+    /// Set the alias of the device and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn set_alias_wait(&mut self, alias: &str) -> Result<(), Error> {
+        let pend = self.set_alias(alias)?;
+        self.blue.wait_result_variant(pend)
+    }
+    /// Get the Received Signal Strength Indicator of the device from the last time it was discovered.
+    /// Returns `None` when the device isn't currently being discovered.
+    pub fn rssi(&self) -> Option<i16> {
+        self.get_base().rssi
+    }
+    /// Get the advertised transmit power level of the device.
+    pub fn tx_power(&self) -> Option<i16> {
+        self.get_base().tx_power
+    }
+    /// Get the external appearance of the device, as found in the GAP service.
+    pub fn appearance(&self) -> Option<u16> {
+        self.get_base().appearance
+    }
+    /// Get the proposed icon name of the device, using the freedesktop.org icon naming specification.
+    pub fn icon(&self) -> Option<&str> {
+        self.get_base().icon.as_deref()
+    }
+    /// Get the Bluetooth class of the device.
+    pub fn class(&self) -> Option<u32> {
+        self.get_base().class
+    }
+    /// Get the UUIDs of the services available on the device.
+    pub fn uuids(&self) -> &[UUID] {
+        &self.get_base().uuids
+    }
+    /// Get the advertised manufacturer specific data, keyed by company identifier.
+    pub fn manufacturer_data(&self) -> &HashMap<u16, Vec<u8>> {
+        &self.get_base().manu_data
+    }
+    /// Get the advertised service data, keyed by service UUID.
+    pub fn service_data(&self) -> &HashMap<UUID, Vec<u8>> {
+        &self.get_base().serv_data
+    }
+    /// Checks if Bluez has finished resolving the GATT services of the device.
+    pub fn services_resolved(&self) -> bool {
+        self.get_base().services_resolved
+    }
+    #[inline]
+    pub fn trusted(&self) -> bool {
+        self.get_base().trusted.get()
+    }
+    /// Set whether the device is trusted (`true`) or not.
+    /// Trusted devices can connect without the connection being authorized.
+    pub fn set_trusted(
+        &mut self,
+        on: bool,
+    ) -> Result<Pending<Result<(), Error>, (Rc<Cell<bool>>, bool, &'static str)>, Error> {
+        let data = (self.get_base().trusted.clone(), on, bluetooth_cb::TRUSTED);
+        self.set_prop("Trusted", on, data, &bluetooth_cb::set_power_cb)
+    }
+    /// Set whether the device is trusted (`true`) or not and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn set_trusted_wait(&mut self, on: bool) -> Result<(), Error> {
+        let pend = self.set_trusted(on)?;
+        self.blue.wait_result_variant(pend)
+    }
+    #[inline]
+    pub fn blocked(&self) -> bool {
+        self.get_base().blocked.get()
+    }
+    /// Set whether the device is blocked (`true`) or not.
+    /// Incoming connections from blocked devices are rejected.
+    pub fn set_blocked(
+        &mut self,
+        on: bool,
+    ) -> Result<Pending<Result<(), Error>, (Rc<Cell<bool>>, bool, &'static str)>, Error> {
+        let data = (self.get_base().blocked.clone(), on, bluetooth_cb::BLOCKED);
+        self.set_prop("Blocked", on, data, &bluetooth_cb::set_power_cb)
+    }
+    /// Set whether the device is blocked (`true`) or not and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn set_blocked_wait(&mut self, on: bool) -> Result<(), Error> {
+        let pend = self.set_blocked(on)?;
+        self.blue.wait_result_variant(pend)
+    }
+    /// Checks if the device is allowed to wake up the host from system suspend.
+    #[inline]
+    pub fn wake_allowed(&self) -> bool {
+        self.get_base().wake_allowed.get()
+    }
+    /// Set whether the device is allowed to wake up the host from system suspend (`true`) or not.
+    pub fn set_wake_allowed(
+        &mut self,
+        on: bool,
+    ) -> Result<Pending<Result<(), Error>, (Rc<Cell<bool>>, bool, &'static str)>, Error> {
+        let data = (
+            self.get_base().wake_allowed.clone(),
+            on,
+            bluetooth_cb::WAKE_ALLOWED,
+        );
+        self.set_prop("WakeAllowed", on, data, &bluetooth_cb::set_power_cb)
+    }
+    /// Set whether the device is allowed to wake up the host and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn set_wake_allowed_wait(&mut self, on: bool) -> Result<(), Error> {
+        let pend = self.set_wake_allowed(on)?;
+        self.blue.wait_result_variant(pend)
+    }
+    /// Checks if the device only supports the pre-2.1 pairing mechanism.
+    pub fn legacy_pairing(&self) -> bool {
+        self.get_base().legacy_pairing
+    }
+    /// Checks if the pairing with the device has been stored as a bond.
+    /// Always `false` for Bluez versions that don't report the `Bonded` property.
+    pub fn bonded(&self) -> bool {
+        self.get_base().bonded
+    }
+    /// Get the remote device ID information in modalias format.
+    pub fn modalias(&self) -> Option<&str> {
+        self.get_base().modalias.as_deref()
+    }
+    /// Initiate pairing with the remote device.
+    pub fn pair(
+        &mut self,
+    ) -> Result<Pending<Result<(), Error>, (Rc<Cell<bool>>, bool, &'static str)>, Error> {
+        let data = (self.get_base().paired.clone(), true, bluetooth_cb::PAIR);
+        self.device_call("Pair", None, data, &bluetooth_cb::set_power_cb)
+    }
+    /// Pair with the remote device and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn pair_wait(&mut self) -> Result<(), Error> {
+        let pend = self.pair()?;
+        self.blue.wait_result_variant(pend)
+    }
+    /// Cancel a pairing operation started with [`pair()`].
+    ///
+    /// [`pair()`]: ./struct.RemoteDevice.html#method.pair
+    pub fn cancel_pairing(&mut self) -> Result<Pending<Result<(), Error>, &'static str>, Error> {
+        self.device_call(
+            "CancelPairing",
+            None,
+            bluetooth_cb::CANCEL_PAIRING,
+            &bluetooth_cb::method_cb,
+        )
+    }
+    /// Cancel a pairing operation and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn cancel_pairing_wait(&mut self) -> Result<(), Error> {
+        let pend = self.cancel_pairing()?;
+        self.blue.wait_result_variant(pend)
     }
     pub fn forget_service(&mut self, uuid: &UUID) -> bool {
         self.get_base_mut().services.remove(uuid).is_some()
@@ -189,7 +723,8 @@ impl<'a, 'b: 'a> HasChildren<'a> for RemoteDevice<'b> {
         self.get_base().get_children()
     }
     fn get_child<T: ToUUID>(&'a mut self, uuid: T) -> Option<Self::Child> {
-        unimplemented!()
+        let uuid = uuid.to_uuid();
+        self.get_service(&uuid)
     }
 }
 impl<'a, 'c: 'a> Device<'a> for RemoteDevice<'c> {
@@ -203,6 +738,7 @@ impl<'a, 'c: 'a> Device<'a> for RemoteDevice<'c> {
     }
     fn get_service(&'a mut self, uuid: &UUID) -> Option<Self::ServiceType> {
         let base = self.get_base_mut();
+        RemoteServiceBase::resolve_includes(&mut base.services, uuid);
         let _serv_base = base.services.get_mut(uuid)?;
         Some(RemoteService {
             dev: self,
@@ -218,10 +754,15 @@ impl<'a, 'c: 'a> Device<'a> for RemoteDevice<'c> {
         &self.mac
     }
     fn address_type(&mut self) -> AddrType {
-        unimplemented!()
+        self.get_base().addr_type
     }
+    /// Returns the remote name of the device, or its alias if the name is unknown.
     fn name(&mut self) -> String {
-        unimplemented!()
+        let base = self.get_base();
+        match &base.name {
+            Some(name) => name.clone(),
+            None => base.alias.borrow().clone(),
+        }
     }
 }
 impl<'a> HasChildren<'a> for Bluetooth {
@@ -252,12 +793,13 @@ impl<'a, 'b: 'a, 'c: 'a> Device<'a> for Bluetooth {
         self.devices.contains_key(uuid)
     }
     fn address(&self) -> &MAC {
-        unimplemented!()
+        &self.addr
     }
     fn address_type(&mut self) -> AddrType {
-        unimplemented!()
+        self.addr_type
     }
+    /// Returns the alias of the local adapter.
     fn name(&mut self) -> String {
-        unimplemented!()
+        self.alias()
     }
 }
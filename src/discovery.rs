@@ -0,0 +1,143 @@
+use crate::*;
+
+use rustbus::params;
+use rustbus::params::{Base, Container, Param};
+use rustbus::signature;
+
+/// The transport Bluez should use when scanning for devices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transport {
+    /// Interleaved scanning of both transports, or which ever the controller supports.
+    Auto,
+    BrEdr,
+    Le,
+}
+impl Transport {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Transport::Auto => "auto",
+            Transport::BrEdr => "bredr",
+            Transport::Le => "le",
+        }
+    }
+}
+impl Default for Transport {
+    fn default() -> Self {
+        Transport::Auto
+    }
+}
+
+/// Filter used by Bluez to determine which devices are reported during discovery.
+/// It is set with [`Bluetooth::set_discovery_filter()`].
+///
+/// See the `SetDiscoveryFilter` method in the [Adapter API] for more details about what each field does.
+///
+/// [`Bluetooth::set_discovery_filter()`]: ./struct.Bluetooth.html#method.set_discovery_filter
+/// [Adapter API]: https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/adapter-api.txt
+#[derive(Clone, Debug)]
+pub struct DiscoveryFilter {
+    /// Only report devices advertising at least one of these service UUIDs.
+    /// If empty, all devices are reported.
+    pub uuids: Vec<UUID>,
+    /// Only report devices with an RSSI greater than or equal to this value.
+    /// Cannot be used with `pathloss`.
+    pub rssi: Option<i16>,
+    /// Only report devices with a pathloss less than or equal to this value.
+    /// Cannot be used with `rssi`.
+    pub pathloss: Option<u16>,
+    pub transport: Transport,
+    /// Defaults to `true`. Whether to report every advertisement received from a device,
+    /// or only those whose data has changed.
+    pub duplicate_data: bool,
+    /// Defaults to `false`. Only report devices that are discoverable.
+    pub discoverable: bool,
+}
+impl Default for DiscoveryFilter {
+    fn default() -> Self {
+        DiscoveryFilter {
+            uuids: Vec::new(),
+            rssi: None,
+            pathloss: None,
+            transport: Transport::Auto,
+            duplicate_data: true,
+            discoverable: false,
+        }
+    }
+}
+impl DiscoveryFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Validates the UUIDs in the filter, and that `rssi` and `pathloss` are not both set.
+    pub fn validate(&self) -> Result<(), Error> {
+        for uuid in &self.uuids {
+            if !validate_uuid(uuid) {
+                return Err(Error::BadInput(format!(
+                    "{} is an invalid uuid in uuids",
+                    uuid
+                )));
+            }
+        }
+        if self.rssi.is_some() && self.pathloss.is_some() {
+            return Err(Error::BadInput(
+                "rssi and pathloss cannot both be set in a DiscoveryFilter".to_string(),
+            ));
+        }
+        Ok(())
+    }
+    pub(crate) fn to_dict<'a, 'b>(&self) -> params::Dict<'a, 'b> {
+        let mut map = HashMap::new();
+        if !self.uuids.is_empty() {
+            let uuids: Vec<Param> = self
+                .uuids
+                .iter()
+                .map(|x| Param::Base(x.to_string().into()))
+                .collect();
+            let array = params::Array {
+                values: uuids,
+                element_sig: signature::Type::Base(signature::Base::String),
+            };
+            map.insert(
+                Base::String("UUIDs".to_string()),
+                container_param_to_variant(Container::Array(array)),
+            );
+        }
+        if let Some(rssi) = self.rssi {
+            map.insert(
+                Base::String("RSSI".to_string()),
+                base_param_to_variant(Base::Int16(rssi)),
+            );
+        }
+        if let Some(pathloss) = self.pathloss {
+            map.insert(
+                Base::String("Pathloss".to_string()),
+                base_param_to_variant(Base::Uint16(pathloss)),
+            );
+        }
+        // Older versions of Bluez reject keys they don't know,
+        // so the remaining keys are only sent when they differ from Bluez's defaults.
+        if self.transport != Transport::Auto {
+            map.insert(
+                Base::String("Transport".to_string()),
+                base_param_to_variant(self.transport.to_str().to_string().into()),
+            );
+        }
+        if !self.duplicate_data {
+            map.insert(
+                Base::String("DuplicateData".to_string()),
+                base_param_to_variant(self.duplicate_data.into()),
+            );
+        }
+        if self.discoverable {
+            map.insert(
+                Base::String("Discoverable".to_string()),
+                base_param_to_variant(self.discoverable.into()),
+            );
+        }
+        params::Dict {
+            key_sig: signature::Base::String,
+            value_sig: signature::Type::Container(signature::Container::Variant),
+            map,
+        }
+    }
+}
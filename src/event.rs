@@ -0,0 +1,42 @@
+use crate::*;
+
+/// Events caused by changes in Bluez's state, such as devices being discovered or disconnecting.
+///
+/// These are queued while [`Bluetooth::process_requests()`] is called and
+/// can be retrieved with [`Bluetooth::pop_event()`].
+///
+/// [`Bluetooth::process_requests()`]: ./struct.Bluetooth.html#method.process_requests
+/// [`Bluetooth::pop_event()`]: ./struct.Bluetooth.html#method.pop_event
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BluetoothEvent {
+    /// A local adapter was added or removed.
+    Adapter(AdapterEvent),
+    /// The local adapter was powered on (`true`) or off.
+    AdapterPoweredChanged(bool),
+    /// A new remote device was found by Bluez, usually during discovery.
+    DeviceDiscovered(MAC),
+    /// A remote device was removed from Bluez.
+    DeviceRemoved(MAC),
+    Connected(MAC),
+    Disconnected(MAC),
+    /// A remote device was paired (`true`) or unpaired.
+    PairedChanged(MAC, bool),
+    /// A new RSSI was measured for a remote device, usually during discovery.
+    RssiChanged(MAC, i16),
+    /// Bluez finished resolving the GATT services of a remote device.
+    ServicesResolved(MAC),
+    /// The cached value of a remote characteristic changed, such as from a notification.
+    /// Contains the device's `MAC`, and the service's and characteristic's `UUID`s.
+    CharacteristicValueChanged(MAC, UUID, UUID),
+    /// Bluez released one of our advertisements. Contains the advertisement's index.
+    AdvertisementReleased(u16),
+    /// A service was removed from a remote device.
+    /// Contains the device's `MAC` and the service's `UUID`.
+    ServiceRemoved(MAC, UUID),
+    /// A characteristic was removed from a remote service.
+    /// Contains the device's `MAC`, and the service's and characteristic's `UUID`s.
+    CharacteristicRemoved(MAC, UUID, UUID),
+    /// A descriptor was removed from a remote characteristic.
+    /// Contains the device's `MAC`, and the service's, characteristic's and descriptor's `UUID`s.
+    DescriptorRemoved(MAC, UUID, UUID, UUID),
+}
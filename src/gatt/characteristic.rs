@@ -10,11 +10,13 @@ use nix::sys::uio::IoVec;
 use nix::unistd::close;
 use rustbus::params::{Base, Container, Param};
 use rustbus::wire::marshal::traits::UnixFd;
+use rustbus::wire::unmarshal::Error as UnmarshalError;
 use std::cell::Cell;
 use std::convert::TryFrom;
 use std::fmt::Debug;
 use std::os::unix::io::RawFd;
 use std::rc::Weak;
+use std::time::{Duration, Instant};
 
 /// Represents the different write types when writing to characteristics.
 #[derive(Clone, Copy, Debug)]
@@ -32,6 +34,14 @@ impl WriteType {
             WriteType::Reliable => "reliable",
         }
     }
+    pub(crate) fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "command" => Some(WriteType::WithoutRes),
+            "request" => Some(WriteType::WithRes),
+            "reliable" => Some(WriteType::Reliable),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -40,6 +50,96 @@ enum Notify {
     Fd(RawFd, u16),
 }
 
+/// The ATT transaction timeout, used for indications sent by [`LocalChar::notify()`].
+///
+/// [`LocalChar::notify()`]: ./struct.LocalChar.html#method.notify
+const INDICATE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long a reliable write waits for Bluez to reply to a barrier before it is finished anyway.
+const RELIABLE_WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long a `prepare-authorize` authorization is kept for the prepared write to be executed.
+const PREP_AUTHORIZE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// An indication waiting to be sent to, or confirmed by, the remote devices.
+struct QueuedIndication {
+    value: AttValue,
+    timeout: Duration,
+    /// Set once the indication has been sent.
+    deadline: Option<Instant>,
+    cb: Option<Box<dyn FnOnce(IndicateStatus)>>,
+}
+impl QueuedIndication {
+    fn finish(self, status: IndicateStatus) {
+        if let Some(cb) = self.cb {
+            cb(status);
+        }
+    }
+}
+
+/// The UUID of the Characteristic User Description descriptor.
+const USER_DESC_UUID: &'static str = "00002901-0000-1000-8000-00805f9b34fb";
+
+/// A prepared (reliable) write to a local characteristic from a single remote device.
+///
+/// Bluez queues the prepared writes itself and only forwards them to the application
+/// as "reliable" writes, one at a time, once the remote device executes them.
+/// Cancelled prepared writes are dropped by Bluez and never forwarded.
+#[derive(Debug)]
+struct ReliableWrite {
+    /// The value assembled from the executed writes.
+    value: AttValue,
+    ctx: RequestCtx,
+    /// Set when an executed write has been received since the last barrier was sent.
+    unsynced: bool,
+    /// The serial of the outstanding `Ping` sent to Bluez as a barrier.
+    barrier: Option<u32>,
+    /// When the write is finished even if Bluez hasn't replied to the outstanding barrier.
+    deadline: Instant,
+}
+impl ReliableWrite {
+    fn new(value: AttValue, ctx: &RequestCtx) -> Self {
+        ReliableWrite {
+            value,
+            ctx: RequestCtx {
+                offset: 0,
+                write_type: Some(WriteType::Reliable),
+                prepare_authorize: false,
+                ..ctx.clone()
+            },
+            unsynced: false,
+            barrier: None,
+            deadline: Instant::now() + RELIABLE_WRITE_TIMEOUT,
+        }
+    }
+}
+
+/// The Prepare Write requests from a remote device that were authorized with `prepare-authorize`.
+#[derive(Debug)]
+struct PrepAuthorized {
+    /// The offset and value of each authorized prepared write.
+    writes: Vec<(u16, AttValue)>,
+    /// When the authorizations are discarded, if the prepared writes haven't been executed.
+    deadline: Instant,
+}
+impl PrepAuthorized {
+    /// Removes the authorization for the prepared write at `offset` with `bytes`,
+    /// returning whether there was one.
+    fn take(&mut self, offset: u16, bytes: &[u8]) -> bool {
+        match self
+            .writes
+            .iter()
+            .position(|(o, v)| *o == offset && v.as_slice() == bytes)
+        {
+            Some(i) => {
+                self.writes.remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
 /// `LocalCharBase` is used to create GATT characteristics to be added to `LocalServiceBase`.
 pub struct LocalCharBase {
     vf: ValOrFn,
@@ -61,9 +161,48 @@ pub struct LocalCharBase {
     /// while `None` leaves the value the same as it was before the write. The purpose of this allows,
     /// the user to change the ValOrFn before it is set the characteristic, for others to use.
     /// The `bool` is used to indicate whether an notification/indication should be issued after an update.
-    pub write_callback:
-        Option<Box<dyn FnMut(&[u8]) -> Result<(Option<ValOrFn>, bool), (String, Option<String>)>>>,
+    /// The [`RequestCtx`] identifies the remote device, and the offset and type of the write.
+    ///
+    /// For reliable (prepared) writes, the callback is called once with the assembled value
+    /// after Bluez has executed all of the prepared writes. Cancelled prepared writes are discarded
+    /// without calling the callback.
+    ///
+    /// [`RequestCtx`]: ./struct.RequestCtx.html
+    pub write_callback: Option<
+        Box<
+            dyn FnMut(
+                &[u8],
+                &RequestCtx,
+            ) -> Result<(Option<ValOrFn>, bool), (String, Option<String>)>,
+        >,
+    >,
     pub notify_fd_buf: Option<usize>,
+    reliable: HashMap<MAC, ReliableWrite>,
+    /// The prepared writes from each remote device that were authorized with `prepare-authorize`.
+    prep_authorized: HashMap<MAC, PrepAuthorized>,
+    /// Set a callback that takes ownership of writes issued by remote devices, so they can be
+    /// answered later with [`Bluetooth::reply_write()`]. When set, it is used instead of `write_callback`,
+    /// except for writes received through the write fd.
+    ///
+    /// [`Bluetooth::reply_write()`]: ../struct.Bluetooth.html#method.reply_write
+    pub deferred_write: Option<Box<dyn FnMut(&[u8], WriteReply)>>,
+    /// Set a callback that can allow or deny reads and writes issued by remote devices,
+    /// before they are handled. This is normally used with the `authorize` flag, which makes Bluez
+    /// ask the application to authorize Prepare Write requests (see [`RequestCtx::prepare_authorize`]).
+    /// Denied requests are returned to the remote device with the returned error.
+    ///
+    /// [`RequestCtx::prepare_authorize`]: ./struct.RequestCtx.html#structfield.prepare_authorize
+    pub authorize_callback: Option<Box<dyn FnMut(AuthReq) -> Result<(), AttError>>>,
+    /// Set a callback that is called when remote devices subscribe or unsubscribe
+    /// from notifications/indications, or when the notify socket is closed.
+    /// This allows expensive sources of the value to only be sampled while there is a subscriber.
+    pub subscribe_callback: Option<Box<dyn FnMut(SubscribeEvent)>>,
+    /// The context of the `AcquireWrite` call, used for writes received on the write fd.
+    write_ctx: RequestCtx,
+    /// The context of the `AcquireNotify` call, given to the subscribe callback once the socket is closed.
+    notify_ctx: RequestCtx,
+    /// Indications that haven't been confirmed yet. Only the front one has been sent.
+    indications: VecDeque<QueuedIndication>,
 }
 impl Debug for LocalCharBase {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
@@ -78,15 +217,21 @@ impl Debug for LocalCharBase {
 }
 impl Drop for LocalCharBase {
     fn drop(&mut self) {
+        self.close_fds();
+    }
+}
+impl LocalCharBase {
+    /// Closes the notify and write sockets acquired by Bluez, and stops any signal based notifying.
+    pub(crate) fn close_fds(&mut self) {
         if let Some(Notify::Fd(fd, _)) = self.notify {
             close(fd).ok(); // ignore error
         }
-        if let Some(fd) = self.write {
+        self.notify = None;
+        self.cancel_indications();
+        if let Some(fd) = self.write.take() {
             close(fd).ok();
         }
     }
-}
-impl LocalCharBase {
     /// Enables `AcquireWrite` DBus call to be issued by Bluez to the local application.
     /// [`AcquireWrite`] will allow Bluez to issue writes to the local characteristic, using
     /// packets over a Unix socket. This can have better performance and lower latency by allowing
@@ -102,6 +247,49 @@ impl LocalCharBase {
             }
         }
     }
+    /// Closes the notify socket from `AcquireNotify`, if there is one, and calls the subscribe callback.
+    fn close_notify_fd(&mut self) {
+        if let Some(Notify::Fd(fd, _)) = self.notify {
+            close(fd).ok();
+            self.notify = None;
+            self.cancel_indications();
+            let ctx = std::mem::take(&mut self.notify_ctx);
+            if let Some(cb) = &mut self.subscribe_callback {
+                cb(SubscribeEvent::SocketClosed(ctx));
+            }
+        }
+    }
+    /// Checks if the notify socket from `AcquireNotify` has been closed by Bluez.
+    pub(crate) fn check_notify_fd(&mut self) {
+        if let Some(Notify::Fd(fd, _)) = self.notify {
+            let mut poll_fds = [poll::PollFd::new(fd, poll::PollFlags::empty())];
+            if let Err(_) = poll::poll(&mut poll_fds, 0) {
+                return;
+            }
+            if let Some(evts) = poll_fds[0].revents() {
+                if evts.intersects(poll::PollFlags::POLLHUP | poll::PollFlags::POLLERR) {
+                    self.close_notify_fd();
+                }
+            }
+        }
+    }
+    /// Drops the queued indications, because there is no longer a remote device to confirm them.
+    fn cancel_indications(&mut self) {
+        for ind in self.indications.drain(..) {
+            ind.finish(IndicateStatus::Cancelled);
+        }
+    }
+    /// Checks if there are indications that haven't been confirmed yet.
+    pub(crate) fn has_indications(&self) -> bool {
+        !self.indications.is_empty()
+    }
+    pub(crate) fn set_vf(&mut self, vf: ValOrFn) {
+        self.vf = vf;
+    }
+    /// Checks if any remote devices have a reliable write in progress.
+    pub(crate) fn has_reliable_writes(&self) -> bool {
+        !self.reliable.is_empty() || !self.prep_authorized.is_empty()
+    }
     pub(super) fn update_path(&mut self, base: &Path) {
         self.path = base.to_owned();
         let mut name = String::with_capacity(8);
@@ -134,10 +322,24 @@ impl LocalCharBase {
             write_callback: None,
             serv_uuid: Rc::from(""),
             notify_fd_buf: None,
+            reliable: HashMap::new(),
+            prep_authorized: HashMap::new(),
+            deferred_write: None,
+            authorize_callback: None,
+            subscribe_callback: None,
+            write_ctx: RequestCtx::default(),
+            notify_ctx: RequestCtx::default(),
+            indications: VecDeque::new(),
         }
     }
     /// Adds a local descritpor to the characteristic.
+    ///
+    /// If the characteristic has the `writable_auxiliaries` flag, the Characteristic User Description
+    /// descriptor (0x2901) is made writable, as required by the Extended Properties.
     pub fn add_desc(&mut self, mut desc: LocalDescBase) {
+        if self.flags.writable_auxiliaries && desc.uuid.eq_ignore_ascii_case(USER_DESC_UUID) {
+            desc.flags.write = true;
+        }
         desc.index = self.desc_index;
         desc.char_uuid = self.uuid.clone();
         self.desc_index += 1;
@@ -187,9 +389,9 @@ impl<'c, 'd> LocalChar<'c, 'd> {
     pub(crate) fn get_blue(&mut self) -> &Bluetooth {
         &self.service.bt
     }
-    pub(crate) fn char_call<'a, 'b>(&mut self, call: MarshalledMessage) -> MarshalledMessage {
+    pub(crate) fn char_call(&mut self, call: MarshalledMessage) -> Option<MarshalledMessage> {
         let base = self.get_char_base_mut();
-        match &call.dynheader.member.as_ref().unwrap()[..] {
+        let reply = match &call.dynheader.member.as_ref().unwrap()[..] {
             "ReadValue" => {
                 if base.flags.read
                     || base.flags.secure_read
@@ -197,54 +399,50 @@ impl<'c, 'd> LocalChar<'c, 'd> {
                     || base.flags.encrypt_read
                 {
                     self.check_write_fd();
-                    let base = self.get_char_base_mut();
-                    let call = call.unmarshall_all().unwrap();
-                    let cv = base.vf.to_value();
-                    let mut start = 0;
-                    if let Some(dict) = call.params.get(0) {
-                        if let Param::Container(Container::Dict(dict)) = dict {
-                            if let Some(offset) = dict.map.get(&Base::String("offset".to_string()))
-                            {
-                                if let Param::Container(Container::Variant(offset)) = offset {
-                                    if let Param::Base(Base::Uint16(offset)) = offset.value {
-                                        start = cv.len().min(offset as usize);
-                                    } else {
-                                        return call.dynheader.make_error_response(
-                                            "UnexpectedType".to_string(),
-                                            Some(
-                                                "Expected a dict of variants as first parameter"
-                                                    .to_string(),
-                                            ),
-                                        );
-                                    }
-                                } else {
-                                    return call.dynheader.make_error_response(
-                                        "UnexpectedType".to_string(),
-                                        Some(
-                                            "Expected a dict of variants as first parameter"
-                                                .to_string(),
-                                        ),
-                                    );
-                                }
+                    let dict: HashMap<String, Variant> = match call.body.parser().get() {
+                        Ok(d) => d,
+                        Err(e) => match e {
+                            UnmarshalError::EndOfMessage => HashMap::new(),
+                            _ => {
+                                return Some(call.dynheader.make_error_response(
+                                    "UnexpectedType".to_string(),
+                                    Some("Expected a dict as first parameter".to_string()),
+                                ))
                             }
-                        } else {
-                            return call.dynheader.make_error_response(
-                                "UnexpectedType".to_string(),
-                                Some("Expected a dict as first parameter".to_string()),
-                            );
+                        },
+                    };
+                    let ctx = match RequestCtx::from_dict(&dict) {
+                        Ok(ctx) => ctx,
+                        Err(err) => {
+                            return Some(
+                                call.dynheader
+                                    .make_error_response("UnexpectedType".to_string(), Some(err)),
+                            )
                         }
+                    };
+                    if let Some(device) = &ctx.device {
+                        self.finish_reliable_write(device);
                     }
-                    // eprintln!("vf: {:?}\nValue: {:?}", base.vf, &v[..l]);
-                    let vec: Vec<Param> = cv.as_slice()[start..]
-                        .into_iter()
-                        .map(|i| Base::Byte(*i).into())
-                        .collect();
-                    let val = Param::Container(Container::Array(params::Array {
-                        element_sig: signature::Type::Base(signature::Base::Byte),
-                        values: vec,
-                    }));
-                    let mut res = call.make_response();
-                    res.body.push_old_param(&val).unwrap();
+                    let base = self.get_char_base_mut();
+                    if let Some(cb) = &mut base.authorize_callback {
+                        if let Err(err) = cb(AuthReq::Read(&ctx)) {
+                            return Some(err.to_response(&call.dynheader));
+                        }
+                    }
+                    if let ValOrFn::Deferred(f) = &mut base.vf {
+                        f(ReadReply::new(call.dynheader.clone(), ctx));
+                        return None;
+                    }
+                    let cv = match base.vf.try_to_value_with_ctx(&ctx) {
+                        Ok(cv) => cv,
+                        Err(err) => return Some(err.to_response(&call.dynheader)),
+                    };
+                    let start = ctx.offset as usize;
+                    if start > cv.len() {
+                        return Some(AttError::InvalidOffset.to_response(&call.dynheader));
+                    }
+                    let mut res = call.dynheader.make_response();
+                    res.body.push_param(&cv[start..]).unwrap();
                     res
                 } else {
                     call.dynheader.make_error_response(
@@ -261,98 +459,144 @@ impl<'c, 'd> LocalChar<'c, 'd> {
                     || base.flags.encrypt_auth_write
                 {
                     self.check_write_fd();
-                    let base = self.get_char_base_mut();
-                    let call = call.unmarshall_all().unwrap();
-                    if let Some(Param::Container(Container::Array(array))) = call.params.get(0) {
-                        let offset = if let Some(dict) = call.params.get(1) {
-                            let mut offset = 0;
-                            if let Param::Container(Container::Dict(dict)) = dict {
-                                for (key, val) in &dict.map {
-                                    let var = if let Param::Container(Container::Variant(var)) = val
-                                    {
-                                        var
-                                    } else {
-                                        return call.dynheader.make_error_response("org.bluez.Error.Failed".to_string(), Some("Second parameter was wrong type, expected variant values".to_string()));
-                                    };
-                                    if let Base::String(key) = key {
-                                        match &key[..] {
-                                            "offset" => {
-                                                if let Param::Base(Base::Uint16(off)) = var.value {
-                                                    offset = off;
-                                                } else {
-                                                    return call.dynheader.make_error_response(
-                                                        "org.bluez.Error.Failed".to_string(),
-                                                        Some(
-                                                            "Expected offset key to be u16 value"
-                                                                .to_string(),
-                                                        ),
-                                                    );
-                                                }
-                                            }
-                                            _ => {}
-                                        }
-                                    } else {
-                                        return call.dynheader.make_error_response("org.bluez.Error.Failed".to_string(), Some("Second parameter was wrong type, expected string keys".to_string()));
-                                    }
-                                }
-                                offset as usize
-                            } else {
-                                return call.dynheader.make_error_response(
-                                    "org.bluez.Error.Failed".to_string(),
-                                    Some("Second parameter was wrong type".to_string()),
-                                );
+                    let mut parser = call.body.parser();
+                    let bytes: &[u8] = match parser.get() {
+                        Ok(bytes) => bytes,
+                        Err(_) => {
+                            return Some(call.dynheader.make_error_response(
+                                BLUEZ_FAILED.to_string(),
+                                Some("Expected byte array as first parameter.".to_string()),
+                            ))
+                        }
+                    };
+                    let dict: HashMap<String, Variant> = match parser.get() {
+                        Ok(d) => d,
+                        Err(e) => match e {
+                            UnmarshalError::EndOfMessage => HashMap::new(),
+                            _ => {
+                                return Some(call.dynheader.make_error_response(
+                                    BLUEZ_FAILED.to_string(),
+                                    Some("Expected dict as second parameter.".to_string()),
+                                ))
                             }
-                        } else {
-                            0
+                        },
+                    };
+                    let ctx = match RequestCtx::from_dict(&dict) {
+                        Ok(ctx) => ctx,
+                        Err(err) => {
+                            return Some(
+                                call.dynheader
+                                    .make_error_response(BLUEZ_FAILED.to_string(), Some(err)),
+                            )
+                        }
+                    };
+                    let offset = ctx.offset as usize;
+                    if bytes.len() + offset > 512 {
+                        return Some(
+                            call.dynheader
+                                .make_error_response(BLUEZ_INVALID_LEN.to_string(), None),
+                        );
+                    }
+                    let base = self.get_char_base_mut();
+                    let prepared = ctx.prepare_authorize
+                        || match ctx.write_type {
+                            Some(WriteType::Reliable) => true,
+                            _ => false,
                         };
-                        let l = array.values.len() + offset;
-                        if l > 512 {
-                            return call
-                                .dynheader
-                                .make_error_response(BLUEZ_INVALID_LEN.to_string(), None);
+                    let device = match &ctx.device {
+                        Some(device) => device.clone(),
+                        None if prepared => {
+                            // Prepared writes are tracked per device, so they can't be handled without one.
+                            return Some(call.dynheader.make_error_response(
+                                BLUEZ_FAILED.to_string(),
+                                Some("Prepared write is missing the device option.".to_string()),
+                            ));
                         }
-                        let mut bytes = Vec::with_capacity(array.values.len());
-                        for val in &array.values {
-                            if let Param::Base(Base::Byte(b)) = val {
-                                bytes.push(*b);
-                            } else {
-                                return call.dynheader.make_error_response(
-                                    "org.bluez.Error.Failed".to_string(),
-                                    Some("First parameter was wrong type".to_string()),
-                                );
+                        None => Rc::from(""),
+                    };
+                    if ctx.prepare_authorize {
+                        // Bluez is only asking to authorize a Prepare Write request.
+                        // Bluez queues the prepared writes, and sends them again with
+                        // the "reliable" type if they are executed.
+                        if let Some(cb) = &mut base.authorize_callback {
+                            if let Err(err) = cb(AuthReq::Write(&ctx, bytes)) {
+                                return Some(err.to_response(&call.dynheader));
                             }
                         }
-                        let mut cur_cv = base.vf.to_value();
-                        cur_cv.update(&bytes, offset);
-                        if let Some(cb) = &mut base.write_callback {
-                            match cb(&cur_cv.as_slice()) {
-                                Ok((vf, notify)) => {
-                                    if let Some(vf) = vf {
-                                        base.vf = vf;
-                                    }
-                                    if notify {
-                                        // TODO: is there a better way to handle this error?
-                                        if let Err(e) = self.notify() {
-                                            eprintln!(
-                                                "Failed to notify characteristic on change: {:?}",
-                                                e
-                                            );
-                                        }
+                        let deadline = Instant::now() + PREP_AUTHORIZE_TIMEOUT;
+                        let auth =
+                            base.prep_authorized
+                                .entry(device)
+                                .or_insert_with(|| PrepAuthorized {
+                                    writes: Vec::new(),
+                                    deadline,
+                                });
+                        auth.writes.push((ctx.offset, AttValue::from(bytes)));
+                        auth.deadline = deadline;
+                        return Some(call.dynheader.make_response());
+                    }
+                    if let Some(WriteType::Reliable) = ctx.write_type {
+                        // Executed prepared writes are buffered until all of them have been received.
+                        // The write only needs to be authorized once, which may have happened when
+                        // this exact write was prepared.
+                        let authorized = match base.prep_authorized.get_mut(&device) {
+                            Some(auth) => auth.take(ctx.offset, bytes),
+                            None => false,
+                        };
+                        if !base.reliable.contains_key(&device) {
+                            if !authorized {
+                                if let Some(cb) = &mut base.authorize_callback {
+                                    if let Err(err) = cb(AuthReq::Write(&ctx, bytes)) {
+                                        return Some(err.to_response(&call.dynheader));
                                     }
                                 }
-                                Err((s1, s2)) => {
-                                    return call.dynheader.make_error_response(s1, s2);
-                                }
                             }
-                        } else {
-                            base.vf = ValOrFn::Value(cur_cv);
+                            let cur_val = base.vf.to_value();
+                            base.reliable
+                                .insert(device.clone(), ReliableWrite::new(cur_val, &ctx));
+                        }
+                        let session = base.reliable.get_mut(&device).unwrap();
+                        if offset > session.value.len() {
+                            // Bluez aborts the execution, so none of the value is written.
+                            base.reliable.remove(&device);
+                            base.prep_authorized.remove(&device);
+                            return Some(
+                                call.dynheader
+                                    .make_error_response(BLUEZ_INVALID_OFFSET.to_string(), None),
+                            );
                         }
-                        call.dynheader.make_response()
-                    } else {
-                        call.dynheader.make_error_response(
-                            "org.bluez.Error.Failed".to_string(),
-                            Some("First parameter was wrong type".to_string()),
-                        )
+                        session.value.update(bytes, offset);
+                        session.unsynced = true;
+                        return Some(call.dynheader.make_response());
+                    }
+                    // Any other write means the previous reliable write from this device has completed.
+                    self.finish_reliable_write(&device);
+                    let base = self.get_char_base_mut();
+                    if let Some(cb) = &mut base.authorize_callback {
+                        if let Err(err) = cb(AuthReq::Write(&ctx, bytes)) {
+                            return Some(err.to_response(&call.dynheader));
+                        }
+                    }
+                    let mut cur_cv = self.get_char_base_mut().vf.to_value();
+                    if offset > cur_cv.len() {
+                        return Some(
+                            call.dynheader
+                                .make_error_response(BLUEZ_INVALID_OFFSET.to_string(), None),
+                        );
+                    }
+                    cur_cv.update(bytes, offset);
+                    let target = self.reply_target();
+                    let base = self.get_char_base_mut();
+                    if let Some(cb) = &mut base.deferred_write {
+                        cb(
+                            &cur_cv,
+                            WriteReply::new(Some(call.dynheader.clone()), ctx, target),
+                        );
+                        return None;
+                    }
+                    match self.apply_write(cur_cv, &ctx) {
+                        Ok(()) => call.dynheader.make_response(),
+                        Err((s1, s2)) => call.dynheader.make_error_response(s1, s2),
                     }
                 } else {
                     call.dynheader.make_error_response(
@@ -363,17 +607,20 @@ impl<'c, 'd> LocalChar<'c, 'd> {
             }
             "AcquireWrite" => {
                 if !base.allow_write {
-                    return call
-                        .dynheader
-                        .make_error_response("org.bluez.Error.NotSupported".to_string(), None);
+                    return Some(
+                        call.dynheader
+                            .make_error_response("org.bluez.Error.NotSupported".to_string(), None),
+                    );
                 }
                 if base.flags.write_wo_response {
                     if let Some(_) = base.write {
-                        return call.dynheader.make_error_response(
-                            "org.bluez.Error.InProgress".to_string(),
-                            Some(
-                                "This characteristic write fd has already been acquired."
-                                    .to_string(),
+                        return Some(
+                            call.dynheader.make_error_response(
+                                "org.bluez.Error.InProgress".to_string(),
+                                Some(
+                                    "This characteristic write fd has already been acquired."
+                                        .to_string(),
+                                ),
                             ),
                         );
                     }
@@ -384,52 +631,51 @@ impl<'c, 'd> LocalChar<'c, 'd> {
                         socket::SockFlag::SOCK_CLOEXEC,
                     ) {
                         Ok((sock1, sock2)) => {
-                            let call = call.unmarshall_all().unwrap();
-                            let mut ret = 517;
-                            if let Some(dict) = call.params.get(0) {
-                                if let Param::Container(Container::Dict(dict)) = dict {
-                                    if let Some(mtu) =
-                                        dict.map.get(&Base::String("mtu".to_string()))
-                                    {
-                                        if let Param::Container(Container::Variant(mtu)) = mtu {
-                                            if let Param::Base(Base::Uint16(mtu)) = mtu.value {
-                                                ret = ret.min(mtu);
-                                            } else {
-                                                close(sock1).ok();
-                                                close(sock2).ok();
-                                                return call.dynheader.make_error_response("UnexpectedType".to_string(), Some("Expected a UInt16 as variant type for offset key.".to_string()));
-                                            }
-                                        } else {
-                                            close(sock1).ok();
-                                            close(sock2).ok();
-                                            return call.dynheader.make_error_response("UnexpectedType".to_string(), Some("Expected a dict of variants as first parameter".to_string()));
-                                        }
+                            let dict: HashMap<String, Variant> = match call.body.parser().get() {
+                                Ok(d) => d,
+                                Err(e) => match e {
+                                    UnmarshalError::EndOfMessage => HashMap::new(),
+                                    _ => {
+                                        close(sock1).ok();
+                                        close(sock2).ok();
+                                        return Some(call.dynheader.make_error_response(
+                                            "UnexpectedType".to_string(),
+                                            Some("Expected a dict as first parameter".to_string()),
+                                        ));
                                     }
-                                } else {
+                                },
+                            };
+                            let mut ctx = match RequestCtx::from_dict(&dict) {
+                                Ok(ctx) => ctx,
+                                Err(err) => {
                                     close(sock1).ok();
                                     close(sock2).ok();
-                                    return call.dynheader.make_error_response(
+                                    return Some(call.dynheader.make_error_response(
                                         "UnexpectedType".to_string(),
-                                        Some("Expected a dict as first parameter".to_string()),
-                                    );
+                                        Some(err),
+                                    ));
                                 }
-                            }
-                            let mut res = call.make_response();
+                            };
+                            let ret = ctx.mtu.map_or(517, |mtu| mtu.min(517));
+                            ctx.write_type = Some(WriteType::WithoutRes);
+                            let mut res = call.dynheader.make_response();
                             res.raw_fds.push(sock1);
                             res.dynheader.num_fds = Some(1);
                             res.body.push_param2(UnixFd(0), ret).unwrap();
+                            let base = self.get_char_base_mut();
+                            base.write_ctx = ctx;
                             base.write = Some(sock2);
-                            return res;
+                            return Some(res);
                         }
-                        Err(_) => {
-                            return call.dynheader.make_error_response(
+                        Err(_) => return Some(
+                            call.dynheader.make_error_response(
                                 BLUEZ_FAILED.to_string(),
                                 Some(
                                     "An IO Error occured when creating the unix datagram socket."
                                         .to_string(),
                                 ),
-                            )
-                        }
+                            ),
+                        ),
                     }
                 } else {
                     call.dynheader.make_error_response(
@@ -471,55 +717,54 @@ impl<'c, 'd> LocalChar<'c, 'd> {
                                 if let Err(_) = cb() {
                                     close(sock1).ok();
                                     close(sock2).ok();
-                                    return call.dynheader.make_error_response(
+                                    return Some(call.dynheader.make_error_response(
        			                         BLUEZ_FAILED.to_string(),
                		                 Some(
                    		                 "An IO Error occured when creating the unix datagram socket."
                        	                 .to_string(),
                        		         ),
-                           		 	);
+                           		 	));
                                 }
                             }
-                            let call = call.unmarshall_all().unwrap();
-                            let mut ret = 255;
-                            if let Some(dict) = call.params.get(0) {
-                                if let Param::Container(Container::Dict(dict)) = dict {
-                                    if let Some(mtu) =
-                                        dict.map.get(&Base::String("mtu".to_string()))
-                                    {
-                                        if let Param::Container(Container::Variant(mtu)) = mtu {
-                                            if let Param::Base(Base::Uint16(mtu)) = mtu.value {
-                                                ret = ret.min(mtu);
-                                            } else {
-                                                close(sock1).ok();
-                                                close(sock2).ok();
-                                                return call.dynheader.make_error_response("UnexpectedType".to_string(), Some("Expected a dict of UInt16 as first offset type".to_string()));
-                                            }
-                                        } else {
-                                            close(sock1).ok();
-                                            close(sock2).ok();
-                                            return call.dynheader.make_error_response("UnexpectedType".to_string(), Some("Expected a dict of variants as first parameter".to_string()));
-                                        }
+                            let dict: HashMap<String, Variant> = match call.body.parser().get() {
+                                Ok(d) => d,
+                                Err(e) => match e {
+                                    UnmarshalError::EndOfMessage => HashMap::new(),
+                                    _ => {
+                                        close(sock1).ok();
+                                        close(sock2).ok();
+                                        return Some(call.dynheader.make_error_response(
+                                            "UnexpectedType".to_string(),
+                                            Some("Expected a dict as first parameter".to_string()),
+                                        ));
                                     }
-                                } else {
+                                },
+                            };
+                            let ctx = match RequestCtx::from_dict(&dict) {
+                                Ok(ctx) => ctx,
+                                Err(err) => {
                                     close(sock1).ok();
                                     close(sock2).ok();
-                                    return call.dynheader.make_error_response(
+                                    return Some(call.dynheader.make_error_response(
                                         "UnexpectedType".to_string(),
-                                        Some("Expected a dict as first parameter".to_string()),
-                                    );
+                                        Some(err),
+                                    ));
                                 }
-                            }
-                            let mut res = call.make_response();
-                            res.body
-                                .push_old_params(&[
-                                    Param::Base(Base::UnixFd(0)),
-                                    Param::Base(Base::Uint16(ret)),
-                                ])
-                                .unwrap();
-                            res.dynheader.num_fds = Some(1);
+                            };
+                            let ret = ctx.mtu.map_or(255, |mtu| mtu.min(255));
+                            let mut res = call.dynheader.make_response();
                             res.raw_fds.push(sock1);
+                            res.dynheader.num_fds = Some(1);
+                            res.body.push_param2(UnixFd(0), ret).unwrap();
+                            let base = self.get_char_base_mut();
                             base.notify = Some(Notify::Fd(sock2, ret));
+                            if let Some(cb) = &mut base.subscribe_callback {
+                                cb(SubscribeEvent::Subscribed {
+                                    ctx: ctx.clone(),
+                                    acquired: true,
+                                });
+                            }
+                            base.notify_ctx = ctx;
                             res
                         }
                         Err(_) => call.dynheader.make_error_response(
@@ -551,12 +796,25 @@ impl<'c, 'd> LocalChar<'c, 'd> {
                     )
                 } else {
                     base.notify = Some(Notify::Signal);
+                    if let Some(cb) = &mut base.subscribe_callback {
+                        cb(SubscribeEvent::Subscribed {
+                            ctx: RequestCtx::default(),
+                            acquired: false,
+                        });
+                    }
                     call.dynheader.make_response()
                 }
             }
             "StopNotify" => {
-                if let Some(_) = base.notify.as_ref() {
-                    base.notify = None;
+                if let Some(notify) = base.notify.take() {
+                    if let Notify::Fd(fd, _) = notify {
+                        close(fd).ok();
+                    }
+                    base.cancel_indications();
+                    let ctx = std::mem::take(&mut base.notify_ctx);
+                    if let Some(cb) = &mut base.subscribe_callback {
+                        cb(SubscribeEvent::Unsubscribed(ctx));
+                    }
                     call.dynheader.make_response()
                 } else {
                     call.dynheader.make_error_response(
@@ -567,17 +825,144 @@ impl<'c, 'd> LocalChar<'c, 'd> {
             }
             "Confirm" => {
                 self.check_write_fd();
+                let base = self.get_char_base_mut();
+                let sent = match base.indications.front() {
+                    Some(ind) => ind.deadline.is_some(),
+                    None => false,
+                };
+                if sent {
+                    let ind = base.indications.pop_front().unwrap();
+                    ind.finish(IndicateStatus::Confirmed);
+                    self.send_indication();
+                }
                 call.dynheader.make_response()
             }
             _ => call
                 .dynheader
                 .make_error_response(UNKNOWN_METHOD.to_string(), None),
+        };
+        Some(reply)
+    }
+    pub(crate) fn reply_target(&self) -> ReplyTarget {
+        ReplyTarget {
+            serv_uuid: self.service.uuid.clone(),
+            char_uuid: self.uuid.clone(),
+            desc_uuid: None,
         }
     }
     pub fn write_val_or_fn(&mut self, val: &mut ValOrFn) {
         let base = self.get_char_base_mut();
         std::mem::swap(&mut base.vf, val);
     }
+    /// Passes a value written by a remote device to the write callback, if there is one,
+    /// and updates the value of the characteristic.
+    fn apply_write(
+        &mut self,
+        val: AttValue,
+        ctx: &RequestCtx,
+    ) -> Result<(), (String, Option<String>)> {
+        let base = self.get_char_base_mut();
+        if let Some(cb) = &mut base.write_callback {
+            let (vf, notify) = cb(val.as_slice(), ctx)?;
+            if let Some(vf) = vf {
+                base.vf = vf;
+            }
+            if notify {
+                // TODO: is there a better way to handle this error?
+                if let Err(e) = self.notify() {
+                    eprintln!("Failed to notify characteristic on change: {:?}", e);
+                }
+            }
+        } else {
+            base.vf = ValOrFn::Value(val);
+        }
+        Ok(())
+    }
+    /// Ends the reliable write session of `device`, writing the assembled value to the
+    /// characteristic (calling the write callback once).
+    ///
+    /// Any remaining `prepare-authorize` authorizations from `device` are discarded,
+    /// so they can't be used to skip authorizing a later write.
+    fn finish_reliable_write(&mut self, device: &MAC) {
+        let base = self.get_char_base_mut();
+        base.prep_authorized.remove(device);
+        let session = match base.reliable.remove(device) {
+            Some(session) => session,
+            None => return,
+        };
+        if let Some(serial) = session.barrier {
+            // The reply to the barrier is no longer needed, so let it be discarded.
+            let blue = self.get_blue_mut();
+            blue.leaking
+                .borrow_mut()
+                .push_back((serial, Box::new(|_: MarshalledMessage| ())));
+        }
+        let target = self.reply_target();
+        let base = self.get_char_base_mut();
+        if let Some(cb) = &mut base.deferred_write {
+            cb(&session.value, WriteReply::new(None, session.ctx, target));
+            return;
+        }
+        // Bluez has already responded to the Execute Write, so errors can't be returned to the client.
+        if let Err(e) = self.apply_write(session.value, &session.ctx) {
+            if self.get_blue().verbose >= 1 {
+                eprintln!("Write callback rejected a reliable write: {:?}", e);
+            }
+        }
+    }
+    /// Sends a `Ping` to Bluez to be used as a barrier.
+    fn send_barrier(&mut self) -> Result<u32, Error> {
+        let mut msg = MessageBuilder::new()
+            .call("Ping".to_string())
+            .on("/".to_string())
+            .at(BLUEZ_DEST.to_string())
+            .with_interface(PEER_IF_STR.to_string())
+            .build();
+        let blue = self.get_blue_mut();
+        Ok(blue.rpc_con.send_message(&mut msg, Timeout::Infinite)?)
+    }
+    /// Finishes the reliable write sessions that Bluez has forwarded all of the executed writes for.
+    ///
+    /// Bluez doesn't signal the end of an Execute Write, so once the replies to the executed writes
+    /// have been sent, a barrier is sent to Bluez. If Bluez replies to it without having sent another
+    /// executed write, the execution is complete. If Bluez doesn't reply in time, the write is
+    /// finished anyway. Expired `prepare-authorize` authorizations are also discarded.
+    pub(crate) fn check_reliable_writes(&mut self) -> Result<(), Error> {
+        let now = Instant::now();
+        let base = self.get_char_base_mut();
+        base.prep_authorized.retain(|_, auth| auth.deadline > now);
+        let devices: Vec<MAC> = base.reliable.keys().cloned().collect();
+        for device in devices {
+            let session = &self.get_char_base().reliable[&device];
+            let deadline = session.deadline;
+            if let Some(serial) = session.barrier {
+                if let None = self.get_blue_mut().rpc_con.try_get_response(serial) {
+                    if deadline <= now {
+                        self.finish_reliable_write(&device);
+                    }
+                    continue;
+                }
+            }
+            let base = self.get_char_base_mut();
+            let session = base.reliable.get_mut(&device).unwrap();
+            session.barrier = None;
+            if session.unsynced {
+                session.unsynced = false;
+                // This relies on Bluez handling method calls in the order they are received,
+                // and on the reply to each executed write being sent before the next one is.
+                // Since the replies to the writes received so far were sent before the barrier,
+                // Bluez replies to the barrier after forwarding the next executed write, if any.
+                let serial = self.send_barrier()?;
+                let base = self.get_char_base_mut();
+                let session = base.reliable.get_mut(&device).unwrap();
+                session.barrier = Some(serial);
+                session.deadline = now + RELIABLE_WRITE_TIMEOUT;
+            } else {
+                self.finish_reliable_write(&device);
+            }
+        }
+        Ok(())
+    }
     pub fn check_write_fd(&mut self) -> Result<(), Error> {
         let mut base = self.get_char_base_mut();
         if let Some(write_fd) = base.write {
@@ -606,7 +991,7 @@ impl<'c, 'd> LocalChar<'c, 'd> {
                         }
                         let l = recvmsg.bytes;
                         if let Some(cb) = &mut base.write_callback {
-                            match cb(&msg_buf[..l]) {
+                            match cb(&msg_buf[..l], &base.write_ctx) {
                                 Ok((vf, notify)) => {
                                     if let Some(vf) = vf {
                                         base.vf = vf;
@@ -637,10 +1022,7 @@ impl<'c, 'd> LocalChar<'c, 'd> {
         }
         Ok(())
     }
-    fn signal_change(&mut self) -> Result<(), Error> {
-        let base = self.get_char_base_mut();
-        //let (v, l) = self.get_char_base_mut().vf.to_value();
-        let cv = base.vf.to_value();
+    fn signal_change(&mut self, cv: AttValue) -> Result<(), Error> {
         let mut params = Vec::with_capacity(3); // TODO: eliminate this allocations
         params.push(Param::Base(Base::String(CHAR_IF_STR.to_string())));
         let changed_vec: Vec<Param> = cv
@@ -708,26 +1090,174 @@ impl<'c, 'd> LocalChar<'c, 'd> {
         let base = self.get_char_base();
         base.write
     }
+    /// Sends the current value of the characteristic to the remote device, if it is notifying.
+    ///
+    /// If the value is given by a [`ValOrFn::TryFunction`] that fails, nothing is sent.
+    /// If sending on the notify socket from `AcquireNotify` fails, the socket is closed and the error is returned.
+    ///
+    /// If the characteristic only supports indications, or there are indications from [`indicate()`]
+    /// waiting to be confirmed, the value is queued behind them, so only one indication is outstanding at a time.
+    ///
+    /// [`ValOrFn::TryFunction`]: ./enum.ValOrFn.html#variant.TryFunction
+    /// [`indicate()`]: ./struct.LocalChar.html#method.indicate
     pub fn notify(&mut self) -> Result<(), Error> {
         let base = self.get_char_base_mut();
-        if let Some(notify) = &mut base.notify {
-            let cv = base.vf.to_value();
-            match notify {
-                Notify::Signal => self.signal_change()?,
-                Notify::Fd(sock, _) => {
-                    if let Err(_) = socket::send(*sock, cv.as_slice(), socket::MsgFlags::MSG_EOR) {
-                        close(*sock).ok();
-                        base.notify = None;
-                    }
+        if base.notify.is_none() {
+            return Ok(());
+        }
+        let cv = match base.vf.try_to_value_with_ctx(&RequestCtx::default()) {
+            Ok(cv) => cv,
+            Err(_) => return Ok(()),
+        };
+        if base.flags.indicate && (!base.flags.notify || !base.indications.is_empty()) {
+            base.indications.push_back(QueuedIndication {
+                value: cv,
+                timeout: INDICATE_TIMEOUT,
+                deadline: None,
+                cb: None,
+            });
+            self.send_indication();
+            Ok(())
+        } else {
+            let res = self.send_value(cv);
+            if res.is_err() {
+                self.get_char_base_mut().close_notify_fd();
+            }
+            res
+        }
+    }
+    /// Sends the current value of the characteristic to the remote devices as an indication,
+    /// and calls `cb` once it is confirmed, with [`IndicateStatus::Confirmed`], or with
+    /// [`IndicateStatus::TimedOut`] if it isn't confirmed within `timeout`.
+    ///
+    /// The value is read when this is called. If another indication is waiting to be
+    /// confirmed, this one is queued and sent after it, so only one indication is outstanding at a time.
+    /// If the remote devices unsubscribe, the queued indications end with [`IndicateStatus::Cancelled`],
+    /// and indications that can't be sent end with [`IndicateStatus::Failed`].
+    /// Confirmations and timeouts are handled by [`Bluetooth::process_requests()`].
+    ///
+    /// [`IndicateStatus::Confirmed`]: ./enum.IndicateStatus.html#variant.Confirmed
+    /// [`IndicateStatus::TimedOut`]: ./enum.IndicateStatus.html#variant.TimedOut
+    /// [`IndicateStatus::Cancelled`]: ./enum.IndicateStatus.html#variant.Cancelled
+    /// [`IndicateStatus::Failed`]: ./enum.IndicateStatus.html#variant.Failed
+    /// [`Bluetooth::process_requests()`]: ../struct.Bluetooth.html#method.process_requests
+    pub fn indicate(
+        &mut self,
+        timeout: Duration,
+        cb: Option<Box<dyn FnOnce(IndicateStatus)>>,
+    ) -> Result<(), Error> {
+        let base = self.get_char_base_mut();
+        if !base.flags.indicate {
+            return Err(Error::BadInput(
+                "This characteristic doesn't permit indicating.".to_string(),
+            ));
+        }
+        if base.notify.is_none() {
+            return Err(Error::BadInput(
+                "No remote device is subscribed to this characteristic.".to_string(),
+            ));
+        }
+        let cv = match base.vf.try_to_value_with_ctx(&RequestCtx::default()) {
+            Ok(cv) => cv,
+            Err(err) => {
+                return Err(Error::BadInput(format!(
+                    "Failed to get the value to indicate: {:?}",
+                    err
+                )))
+            }
+        };
+        base.indications.push_back(QueuedIndication {
+            value: cv,
+            timeout,
+            deadline: None,
+            cb,
+        });
+        self.send_indication();
+        Ok(())
+    }
+    /// Checks if an indication is waiting to be confirmed by a remote device.
+    pub fn indication_pending(&self) -> bool {
+        let base = self.get_char_base();
+        !base.indications.is_empty()
+    }
+    /// Sends the indication at the front of the queue, if it hasn't been sent yet.
+    /// Indications that fail to send are finished with `IndicateStatus::Failed`,
+    /// and the next one is sent instead.
+    fn send_indication(&mut self) {
+        loop {
+            let base = self.get_char_base_mut();
+            let cv = match base.indications.front_mut() {
+                Some(ind) if ind.deadline.is_none() => {
+                    ind.deadline = Some(Instant::now() + ind.timeout);
+                    ind.value
                 }
+                _ => return,
+            };
+            if let Ok(_) = self.send_value(cv) {
+                return;
+            }
+            let base = self.get_char_base_mut();
+            if let Some(ind) = base.indications.pop_front() {
+                ind.finish(IndicateStatus::Failed);
             }
+            // A notify socket that can't be sent on has been closed by Bluez,
+            // which cancels the rest of the queue.
+            base.close_notify_fd();
+        }
+    }
+    /// Times out the outstanding indication if it hasn't been confirmed in time,
+    /// and sends the next one.
+    pub(crate) fn check_indications(&mut self) {
+        let base = self.get_char_base_mut();
+        let expired = match base.indications.front().and_then(|ind| ind.deadline) {
+            Some(deadline) => Instant::now() >= deadline,
+            None => false,
+        };
+        if expired {
+            let ind = base.indications.pop_front().unwrap();
+            ind.finish(IndicateStatus::TimedOut);
+            self.send_indication();
+        }
+    }
+    /// Sends `cv` to the subscribed remote devices.
+    /// If sending on the notify socket fails, the error is returned and the socket is left open,
+    /// so the caller can finish the value being sent before closing it.
+    fn send_value(&mut self, cv: AttValue) -> Result<(), Error> {
+        let base = self.get_char_base_mut();
+        match &base.notify {
+            Some(Notify::Signal) => self.signal_change(cv)?,
+            Some(Notify::Fd(sock, _)) => {
+                socket::send(*sock, cv.as_slice(), socket::MsgFlags::MSG_EOR)?;
+            }
+            None => (),
         }
         Ok(())
     }
+    /// Sets the callback that is called when remote devices subscribe or unsubscribe from notifications.
+    /// Passing `None` removes the existing callback.
+    pub fn set_subscribe_callback(&mut self, cb: Option<Box<dyn FnMut(SubscribeEvent)>>) {
+        let base = self.get_char_base_mut();
+        base.subscribe_callback = cb;
+    }
+    /// Sets the callback that can allow or deny reads and writes from remote devices.
+    /// Passing `None` allows all requests.
+    pub fn set_authorize_callback(
+        &mut self,
+        cb: Option<Box<dyn FnMut(AuthReq) -> Result<(), AttError>>>,
+    ) {
+        let base = self.get_char_base_mut();
+        base.authorize_callback = cb;
+    }
     pub fn set_write_callback(
         &mut self,
         cb: Option<
-            Box<dyn FnMut(&[u8]) -> Result<(Option<ValOrFn>, bool), (String, Option<String>)>>,
+            Box<
+                dyn FnMut(
+                    &[u8],
+                    &RequestCtx,
+                )
+                    -> Result<(Option<ValOrFn>, bool), (String, Option<String>)>,
+            >,
         >,
     ) {
         let base = self.get_char_base_mut();
@@ -816,17 +1346,17 @@ impl CharFlags {
             ret.push("indicate");
         }
         if self.auth_signed_writes {
-            unimplemented!();
             ret.push("authenticated-signed-writes");
         }
-        if self.extended_properties {
+        // Reliable writes and writable auxiliaries are part of the Extended Properties,
+        // which Bluez needs the flag for to add the descriptor (0x2900).
+        if self.extended_properties || self.reliable_write || self.writable_auxiliaries {
             ret.push("extended-properties");
         }
         if self.reliable_write {
             ret.push("reliable-write");
         }
         if self.writable_auxiliaries {
-            unimplemented!();
             ret.push("writable-auxiliaries");
         }
         if self.encrypt_read {
@@ -848,16 +1378,18 @@ impl CharFlags {
             ret.push("secure-read");
         }
         if self.authorize {
-            unimplemented!();
             ret.push("authorize");
         }
         ret
     }
-    fn from_strings<'a, I>(flags: I) -> CharFlags
+    /// Parses the flags reported by Bluez.
+    /// Flags that aren't known are returned separately instead of being treated as an error.
+    fn from_strings<'a, I>(flags: I) -> (CharFlags, Vec<String>)
     where
         I: IntoIterator<Item = &'a str>,
     {
         let mut ret = CharFlags::default();
+        let mut unknown = Vec::new();
         for flag in flags {
             match flag {
                 "broadcast" => ret.broadcast = true,
@@ -877,10 +1409,10 @@ impl CharFlags {
                 "secure-write" => ret.secure_write = true,
                 "secure-read" => ret.secure_read = true,
                 "authorize" => ret.authorize = true,
-                _ => unreachable!(),
+                _ => unknown.push(flag.to_string()),
             }
         }
-        ret
+        (ret, unknown)
     }
 }
 impl<'a, 'b: 'a, 'c: 'b> HasChildren<'a> for LocalChar<'b, 'c> {
@@ -1091,6 +1623,11 @@ pub(crate) struct RemoteCharBase {
     write_fd: Rc<Cell<Option<(u16, RawFd)>>>,
     path: PathBuf,
     flags: Rc<Cell<CharFlags>>,
+    /// Flags reported by Bluez that aren't known by `CharFlags`, such as ones added by newer versions.
+    unknown_flags: Vec<String>,
+    notifying: Rc<Cell<bool>>,
+    value_cb: Option<Box<dyn FnMut(AttValue)>>,
+    mtu: Option<u16>,
 }
 impl RemoteCharBase {
     pub(crate) fn from_props(
@@ -1121,15 +1658,15 @@ impl RemoteCharBase {
                     ))
                 }
             },
-            None => {
-                return Err(Error::DbusReqErr(
-                    "Invalid characteristic returned; missing Value field".to_string(),
-                ))
-            }
+            // Bluez only includes the Value once it is has been read or notified
+            None => Rc::new(Cell::new(AttValue::default())),
         };
-        let flags = match props.remove("Flags") {
+        let (flags, unknown_flags) = match props.remove("Flags") {
             Some(var) => match var.get::<Vec<&str>>() {
-                Ok(flags) => Rc::new(Cell::new(CharFlags::from_strings(flags))),
+                Ok(flags) => {
+                    let (flags, unknown) = CharFlags::from_strings(flags);
+                    (Rc::new(Cell::new(flags)), unknown)
+                }
                 Err(_) => {
                     return Err(Error::DbusReqErr(
                         "Invalid characteristic returned; Flags field is invalid type".to_string(),
@@ -1142,6 +1679,23 @@ impl RemoteCharBase {
                 ));
             }
         };
+        let notifying = match props.remove(NOTIFYING_PROP) {
+            Some(var) => match var.get::<bool>() {
+                Ok(notifying) => notifying,
+                Err(_) => {
+                    return Err(Error::DbusReqErr(
+                        "Invalid characteristic returned; Notifying field is invalid type"
+                            .to_string(),
+                    ));
+                }
+            },
+            None => false,
+        };
+        // Only newer versions of Bluez expose the MTU of the characteristic
+        let mtu = match props.remove(MTU_PROP) {
+            Some(var) => Some(var.get::<u16>()?),
+            None => None,
+        };
         Ok(RemoteCharBase {
             uuid,
             value,
@@ -1149,7 +1703,11 @@ impl RemoteCharBase {
             notify_fd: None,
             write_fd: Rc::new(Cell::new(None)),
             flags,
+            unknown_flags,
             path,
+            notifying: Rc::new(Cell::new(notifying)),
+            value_cb: None,
+            mtu,
         })
     }
     pub(crate) fn update_from_changed(
@@ -1160,14 +1718,25 @@ impl RemoteCharBase {
             match prop.as_str() {
                 "UUID" => self.uuid = var.get::<String>()?.to_uuid(),
                 "Value" => self.value.set(var.get()?),
-                "Flags" => self
-                    .flags
-                    .set(CharFlags::from_strings(var.get::<Vec<&str>>()?)),
+                "Flags" => {
+                    let (flags, unknown) = CharFlags::from_strings(var.get::<Vec<&str>>()?);
+                    self.flags.set(flags);
+                    self.unknown_flags = unknown;
+                }
+                "Notifying" => self.notifying.set(var.get()?),
+                MTU_PROP => self.mtu = Some(var.get()?),
                 _ => (),
             }
         }
         Ok(())
     }
+    /// Calls the value callback, if one is set, with the current cached value.
+    pub(crate) fn call_value_cb(&mut self) {
+        let val = self.value.get();
+        if let Some(cb) = self.value_cb.as_mut() {
+            cb(val);
+        }
+    }
 }
 impl AttObject for RemoteCharBase {
     fn path(&self) -> &Path {
@@ -1187,11 +1756,20 @@ impl<'a> HasChildren<'a> for RemoteCharBase {
         self.descs.get_mut(&uuid)
     }
 }
-impl Drop for RemoteCharBase {
-    fn drop(&mut self) {
-        if let Some(fd) = self.notify_fd {
+impl RemoteCharBase {
+    /// Closes the acquired notify and write fds, if any.
+    pub(crate) fn close_fds(&mut self) {
+        if let Some(fd) = self.notify_fd.take() {
             close(fd).ok();
         }
+        if let Some((_, fd)) = self.write_fd.take() {
+            close(fd).ok();
+        }
+    }
+}
+impl Drop for RemoteCharBase {
+    fn drop(&mut self) {
+        self.close_fds();
     }
 }
 /// Represents a characteristic present on a remote device.
@@ -1364,7 +1942,7 @@ impl<'a, 'b, 'c> RemoteChar<'a, 'b, 'c> {
     fn get_blue(&mut self) -> &Bluetooth {
         &self.service.dev.blue
     }
-    fn get_blue_mut(&mut self) -> &mut Bluetooth {
+    pub(super) fn get_blue_mut(&mut self) -> &mut Bluetooth {
         self.service.dev.blue
     }
     pub(super) fn get_char_base(&self) -> &RemoteCharBase {
@@ -1411,87 +1989,163 @@ impl<'a, 'b, 'c> RemoteChar<'a, 'b, 'c> {
             None => Err(Error::NoFd(format!("No write fd has been acquired"))),
         }
     }
-    /*pub fn start_notify(&self) -> ();*/
-}
-impl AttObject for RemoteChar<'_, '_, '_> {
-    fn path(&self) -> &Path {
-        self.get_char_base().path()
+    /// Start receiving notifications/indications from the remote characteristic through DBus signals.
+    ///
+    /// Received values update the cached value and are passed to the callback set by
+    /// [`set_value_cb()`], while [`Bluetooth::process_requests()`] is called.
+    /// Unlike [`acquire_notify()`], this works with characteristics that don't support `AcquireNotify`.
+    ///
+    /// [`set_value_cb()`]: ./struct.RemoteChar.html#method.set_value_cb
+    /// [`Bluetooth::process_requests()`]: ../struct.Bluetooth.html#method.process_requests
+    /// [`acquire_notify()`]: ./struct.RemoteChar.html#method.acquire_notify
+    pub fn start_notify(
+        &mut self,
+    ) -> Result<Pending<Result<(), Error>, (Rc<Cell<bool>>, bool, &'static str)>, Error> {
+        self.notify_call("StartNotify", true, bluetooth_cb::START_NOTIFY)
     }
-    fn uuid(&self) -> &UUID {
-        &self.uuid
+    /// Start receiving notifications/indications through DBus signals and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn start_notify_wait(&mut self) -> Result<(), Error> {
+        let pend = self.start_notify()?;
+        self.get_blue_mut().resolve(pend).map_err(|e| e.1)?
     }
-}
-impl<'a, 'b: 'a, 'c: 'b, 'd: 'c> HasChildren<'a> for RemoteChar<'b, 'c, 'd> {
-    type Child = RemoteDesc<'a, 'b, 'c, 'd>;
-    fn get_children(&self) -> Vec<UUID> {
-        self.get_char_base().get_children()
+    /// Stop receiving notifications/indications that were started with [`start_notify()`].
+    ///
+    /// [`start_notify()`]: ./struct.RemoteChar.html#method.start_notify
+    pub fn stop_notify(
+        &mut self,
+    ) -> Result<Pending<Result<(), Error>, (Rc<Cell<bool>>, bool, &'static str)>, Error> {
+        self.notify_call("StopNotify", false, bluetooth_cb::STOP_NOTIFY)
     }
-    fn get_child<T: ToUUID>(&'a mut self, uuid: T) -> Option<Self::Child> {
-        let base = self.get_char_base_mut();
-        let uuid = uuid.to_uuid();
-        if base.descs.contains_key(&uuid) {
-            Some(RemoteDesc {
-                uuid,
-                character: self,
-            })
-        } else {
-            None
-        }
+    /// Stop receiving notifications/indications and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn stop_notify_wait(&mut self) -> Result<(), Error> {
+        let pend = self.stop_notify()?;
+        self.get_blue_mut().resolve(pend).map_err(|e| e.1)?
     }
-}
-
-impl ReadableAtt for RemoteChar<'_, '_, '_> {
-    /// Reads a value from the remote device's characteristic.
-    fn read(&mut self) -> Result<Pending<Result<AttValue, Error>, Rc<Cell<AttValue>>>, Error> {
-        let leaking = Rc::downgrade(&self.get_blue().leaking);
-        let base = self.get_char_base_mut();
-        let path = base.path.to_str().unwrap().to_string();
+    fn notify_call(
+        &mut self,
+        method: &str,
+        on: bool,
+        err_str: &'static str,
+    ) -> Result<Pending<Result<(), Error>, (Rc<Cell<bool>>, bool, &'static str)>, Error> {
+        let base = self.get_char_base();
+        let data = (base.notifying.clone(), on, err_str);
+        let mut msg = MessageBuilder::new()
+            .call(method.to_string())
+            .with_interface(CHAR_IF_STR.to_string())
+            .on(base.path.to_str().unwrap().to_string())
+            .at(BLUEZ_DEST.to_string())
+            .build();
+        let blue = self.get_blue_mut();
+        let res_idx = blue.rpc_con.send_message(&mut msg, Timeout::Infinite)?;
+        Ok(Pending {
+            dbus_res: res_idx,
+            typ: Some(PendingType::MessageCb(&bluetooth_cb::set_power_cb)),
+            data: Some(data),
+            leaking: Rc::downgrade(&blue.leaking),
+        })
+    }
+    /// Checks if Bluez is notifying this characteristic's value changes through DBus signals.
+    /// This is not affected by [`acquire_notify()`].
+    ///
+    /// [`acquire_notify()`]: ./struct.RemoteChar.html#method.acquire_notify
+    pub fn notifying(&self) -> bool {
+        self.get_char_base().notifying.get()
+    }
+    /// Set a callback that is called with the new value whenever Bluez reports the value
+    /// of this characteristic changing, such as after a notification or read.
+    /// The callback is called from [`Bluetooth::process_requests()`].
+    /// Passing `None` removes the existing callback.
+    ///
+    /// [`Bluetooth::process_requests()`]: ../struct.Bluetooth.html#method.process_requests
+    pub fn set_value_cb(&mut self, cb: Option<Box<dyn FnMut(AttValue)>>) {
+        self.get_char_base_mut().value_cb = cb;
+    }
+    /// Returns the ATT MTU of the connection used for this characteristic, if Bluez exposes it.
+    ///
+    /// Only newer versions of Bluez (5.62+) include the `MTU` property.
+    pub fn mtu(&self) -> Option<u16> {
+        self.get_char_base().mtu
+    }
+    /// Returns the flags reported by Bluez for this characteristic that aren't represented by [`CharFlags`].
+    ///
+    /// [`CharFlags`]: ./struct.CharFlags.html
+    pub fn unknown_flags(&self) -> &[String] {
+        &self.get_char_base().unknown_flags
+    }
+    /// Reads the value of the remote characteristic starting at `offset`.
+    ///
+    /// When `offset` is zero, the cached value of the characteristic is updated
+    /// with the result.
+    pub fn read_offset(
+        &mut self,
+        offset: u16,
+    ) -> Result<Pending<Result<AttValue, Error>, Rc<Cell<AttValue>>>, Error> {
+        let base = self.get_char_base();
+        let data = if offset == 0 {
+            base.value.clone()
+        } else {
+            Rc::new(Cell::new(AttValue::default()))
+        };
         let mut msg = MessageBuilder::new()
             .call("ReadValue".to_string())
-            .on(path)
+            .on(base.path.to_str().unwrap().to_string())
             .at(BLUEZ_DEST.to_string())
             .with_interface(CHAR_IF_STR.to_string())
             .build();
-        let cont: Container = (
-            signature::Base::String,
-            signature::Type::Container(signature::Container::Variant),
-            HashMap::new(),
-        )
-            .try_into()
-            .unwrap();
-        msg.body.push_old_param(&mut cont.into()).unwrap();
-        let blue = &mut self.service.dev.blue;
+        let mut options = HashMap::new();
+        if offset != 0 {
+            options.insert("offset", CharVar::U16(offset));
+        }
+        msg.body.push_param(options).unwrap();
+        let blue = self.get_blue_mut();
         let res_idx = blue.rpc_con.send_message(&mut msg, Timeout::Infinite)?;
         Ok(Pending {
-            data: Some(Rc::new(Cell::new(AttValue::default()))), // TODO: update
             dbus_res: res_idx,
             typ: Some(PendingType::MessageCb(&mm_to_charvalue)),
-            leaking,
+            data: Some(data),
+            leaking: Rc::downgrade(&blue.leaking),
         })
-        /*loop {
-            blue.process_requests()?;
-            if let Some(res) = blue.rpc_con.try_get_response(res_idx) {
-            }
-        }*/
     }
-    fn read_wait(&mut self) -> Result<AttValue, Error> {
-        let pend = self.read()?;
-        let blue = self.get_blue_mut();
-        blue.resolve(pend).map_err(|e| e.1)?
+    /// Reads the value of the remote characteristic starting at `offset` and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn read_offset_wait(&mut self, offset: u16) -> Result<AttValue, Error> {
+        let pend = self.read_offset(offset)?;
+        self.get_blue_mut().resolve(pend).map_err(|e| e.1)?
     }
-    fn read_cached(&mut self) -> AttValue {
-        self.get_char_base().value.get()
+    /// Reads the entire value of the remote characteristic, even if it is longer than the MTU.
+    ///
+    /// Bluez already follows a `ReadValue` with Read Blob requests until it has the full value,
+    /// so this is the same as [`read_wait()`].
+    ///
+    /// **Calls process_requests()**
+    ///
+    /// [`read_wait()`]: ./trait.ReadableAtt.html#tymethod.read_wait
+    #[deprecated(note = "Bluez reads long values in a single ReadValue; use `read_wait()` instead")]
+    pub fn read_long_wait(&mut self) -> Result<AttValue, Error> {
+        self.read_wait()
     }
-}
-
-impl WritableAtt for RemoteChar<'_, '_, '_> {
-    fn write(
+    /// Writes `val` to the remote characteristic starting at `offset`.
+    ///
+    /// If a write fd has been acquired with [`acquire_write()`], `offset` is zero,
+    /// and `write_type` is [`WriteType::WithoutRes`], the value is sent through the fd instead of DBus.
+    ///
+    /// [`acquire_write()`]: ./struct.RemoteChar.html#method.acquire_write
+    /// [`WriteType::WithoutRes`]: ./enum.WriteType.html#variant.WithoutRes
+    pub fn write_offset(
         &mut self,
         val: AttValue,
+        offset: u16,
         write_type: WriteType,
     ) -> Result<Pending<Result<(), Error>, ()>, Error> {
         let base = self.get_char_base();
-        if let (WriteType::WithoutRes, Some((mtu, fd))) = (write_type, base.write_fd.get()) {
+        if let (WriteType::WithoutRes, 0, Some((mtu, fd))) =
+            (write_type, offset, base.write_fd.get())
+        {
             let mtu = val.len().min(mtu as usize);
             match socket::send(fd, &val[..mtu], socket::MsgFlags::MSG_EOR) {
                 Ok(_) => {
@@ -1502,11 +2156,14 @@ impl WritableAtt for RemoteChar<'_, '_, '_> {
                         leaking: Weak::new(),
                     })
                 }
-                Err(e) => base.write_fd.set(None),
+                Err(_) => base.write_fd.set(None),
             }
         }
         let mut options = HashMap::new();
         options.insert("type", CharVar::String(write_type.to_str().to_string()));
+        if offset != 0 {
+            options.insert("offset", CharVar::U16(offset));
+        }
         let mut msg = MessageBuilder::new()
             .call("WriteValue".to_string())
             .with_interface(CHAR_IF_STR.to_string())
@@ -1524,10 +2181,92 @@ impl WritableAtt for RemoteChar<'_, '_, '_> {
             leaking: Rc::downgrade(&blue.leaking),
         })
     }
-    fn write_wait(&mut self, val: AttValue, write_type: WriteType) -> Result<(), Error> {
-        let pend = self.write(val, write_type)?;
+    /// Writes `val` to the remote characteristic starting at `offset` and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn write_offset_wait(
+        &mut self,
+        val: AttValue,
+        offset: u16,
+        write_type: WriteType,
+    ) -> Result<(), Error> {
+        let pend = self.write_offset(val, offset, write_type)?;
         self.get_blue_mut().resolve(pend).map_err(|e| e.1)?
     }
+    /// Writes a value that is longer than the MTU to the remote characteristic.
+    ///
+    /// Bluez already splits a long `WriteValue` into Prepare Write requests followed by one Execute Write,
+    /// so this is the same as [`write_wait()`] with [`WriteType::Reliable`] if `reliable` is `true`,
+    /// or [`WriteType::WithRes`] otherwise.
+    ///
+    /// **Calls process_requests()**
+    ///
+    /// [`write_wait()`]: ./trait.WritableAtt.html#tymethod.write_wait
+    /// [`WriteType::Reliable`]: ./enum.WriteType.html#variant.Reliable
+    /// [`WriteType::WithRes`]: ./enum.WriteType.html#variant.WithRes
+    #[deprecated(
+        note = "Bluez writes long values in a single WriteValue; use `write_wait()` instead"
+    )]
+    pub fn write_long_wait(&mut self, val: AttValue, reliable: bool) -> Result<(), Error> {
+        let write_type = if reliable {
+            WriteType::Reliable
+        } else {
+            WriteType::WithRes
+        };
+        self.write_wait(val, write_type)
+    }
+}
+impl AttObject for RemoteChar<'_, '_, '_> {
+    fn path(&self) -> &Path {
+        self.get_char_base().path()
+    }
+    fn uuid(&self) -> &UUID {
+        &self.uuid
+    }
+}
+impl<'a, 'b: 'a, 'c: 'b, 'd: 'c> HasChildren<'a> for RemoteChar<'b, 'c, 'd> {
+    type Child = RemoteDesc<'a, 'b, 'c, 'd>;
+    fn get_children(&self) -> Vec<UUID> {
+        self.get_char_base().get_children()
+    }
+    fn get_child<T: ToUUID>(&'a mut self, uuid: T) -> Option<Self::Child> {
+        let base = self.get_char_base_mut();
+        let uuid = uuid.to_uuid();
+        if base.descs.contains_key(&uuid) {
+            Some(RemoteDesc {
+                uuid,
+                character: self,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl ReadableAtt for RemoteChar<'_, '_, '_> {
+    /// Reads a value from the remote device's characteristic.
+    fn read(&mut self) -> Result<Pending<Result<AttValue, Error>, Rc<Cell<AttValue>>>, Error> {
+        self.read_offset(0)
+    }
+    fn read_wait(&mut self) -> Result<AttValue, Error> {
+        self.read_offset_wait(0)
+    }
+    fn read_cached(&mut self) -> AttValue {
+        self.get_char_base().value.get()
+    }
+}
+
+impl WritableAtt for RemoteChar<'_, '_, '_> {
+    fn write(
+        &mut self,
+        val: AttValue,
+        write_type: WriteType,
+    ) -> Result<Pending<Result<(), Error>, ()>, Error> {
+        self.write_offset(val, 0, write_type)
+    }
+    fn write_wait(&mut self, val: AttValue, write_type: WriteType) -> Result<(), Error> {
+        self.write_offset_wait(val, 0, write_type)
+    }
     fn write_acquired(&self) -> bool {
         unimplemented!()
     }
@@ -16,8 +16,22 @@ pub struct LocalDescBase {
     handle: u16,
     pub vf: ValOrFn,
     pub flags: DescFlags,
-    pub write_callback:
-        Option<Box<dyn FnMut(&[u8]) -> Result<Option<ValOrFn>, (String, Option<String>)>>>,
+    /// Set a callback that is called when writes are issued by a remote device.
+    /// The [`RequestCtx`] identifies the remote device, and the offset and type of the write.
+    ///
+    /// [`RequestCtx`]: ./struct.RequestCtx.html
+    pub write_callback: Option<
+        Box<dyn FnMut(&[u8], &RequestCtx) -> Result<Option<ValOrFn>, (String, Option<String>)>>,
+    >,
+    /// Set a callback that takes ownership of writes issued by remote devices, so they can be
+    /// answered later with [`Bluetooth::reply_write()`]. When set, it is used instead of `write_callback`.
+    ///
+    /// [`Bluetooth::reply_write()`]: ../struct.Bluetooth.html#method.reply_write
+    pub deferred_write: Option<Box<dyn FnMut(&[u8], WriteReply)>>,
+    /// Set a callback that can allow or deny reads and writes issued by remote devices,
+    /// before they are handled. This is normally used with the `authorize` flag.
+    /// Denied requests are returned to the remote device with the returned error.
+    pub authorize_callback: Option<Box<dyn FnMut(AuthReq) -> Result<(), AttError>>>,
 }
 impl LocalDescBase {
     pub fn new<T: ToUUID>(uuid: T, flags: DescFlags) -> Self {
@@ -30,6 +44,8 @@ impl LocalDescBase {
             serv_uuid: Rc::from(""),
             char_uuid: Rc::from(""),
             write_callback: None,
+            deferred_write: None,
+            authorize_callback: None,
             index: 0,
             handle: 0,
         }
@@ -84,9 +100,9 @@ impl<'a, 'b, 'c> LocalDesc<'a, 'b, 'c> {
             .get_mut(&self.uuid)
             .unwrap()
     }
-    pub(crate) fn desc_call(&mut self, call: MarshalledMessage) -> MarshalledMessage {
+    pub(crate) fn desc_call(&mut self, call: MarshalledMessage) -> Option<MarshalledMessage> {
         let base = self.get_desc_base_mut();
-        match &call.dynheader.member.as_ref().unwrap()[..] {
+        let reply = match &call.dynheader.member.as_ref().unwrap()[..] {
             "ReadValue" => {
                 if base.flags.read
                     || base.flags.encrypt_read
@@ -98,33 +114,41 @@ impl<'a, 'b, 'c> LocalDesc<'a, 'b, 'c> {
                         Err(e) => match e {
                             UnmarshalError::EndOfMessage => HashMap::new(),
                             _ => {
-                                return call.dynheader.make_error_response(
+                                return Some(call.dynheader.make_error_response(
                                     BLUEZ_FAILED.to_string(),
                                     Some("Unexpected type for uint 16.".to_string()),
-                                )
+                                ))
                             }
                         },
                     };
-                    let offset = match dict.get("offset") {
-                        Some(v) => match v.get::<u16>() {
-                            Ok(offset) => offset,
-                            Err(_) => {
-                                return call.dynheader.make_error_response(
-                                    BLUEZ_FAILED.to_string(),
-                                    Some("Expected type for 'offset' to be  uint16.".to_string()),
-                                )
-                            }
-                        },
-                        None => 0,
-                    } as usize;
-                    let mut reply = call.dynheader.make_response();
-                    let val = base.vf.to_value();
-                    if offset >= val.len() {
-                        // TODO: should this return an error instead of an empty array
-                        reply.body.push_param::<&[u8]>(&[]).unwrap();
-                    } else {
-                        reply.body.push_param(&val[offset..]).unwrap();
+                    let ctx = match RequestCtx::from_dict(&dict) {
+                        Ok(ctx) => ctx,
+                        Err(err) => {
+                            return Some(
+                                call.dynheader
+                                    .make_error_response(BLUEZ_FAILED.to_string(), Some(err)),
+                            )
+                        }
+                    };
+                    let offset = ctx.offset as usize;
+                    if let Some(cb) = &mut base.authorize_callback {
+                        if let Err(err) = cb(AuthReq::Read(&ctx)) {
+                            return Some(err.to_response(&call.dynheader));
+                        }
                     }
+                    if let ValOrFn::Deferred(f) = &mut base.vf {
+                        f(ReadReply::new(call.dynheader.clone(), ctx));
+                        return None;
+                    }
+                    let val = match base.vf.try_to_value_with_ctx(&ctx) {
+                        Ok(val) => val,
+                        Err(err) => return Some(err.to_response(&call.dynheader)),
+                    };
+                    if offset > val.len() {
+                        return Some(AttError::InvalidOffset.to_response(&call.dynheader));
+                    }
+                    let mut reply = call.dynheader.make_response();
+                    reply.body.push_param(&val[offset..]).unwrap();
                     reply
                 } else {
                     call.dynheader.make_error_response(
@@ -143,10 +167,10 @@ impl<'a, 'b, 'c> LocalDesc<'a, 'b, 'c> {
                     let bytes = match parser.get() {
                         Ok(bytes) => bytes,
                         Err(_) => {
-                            return call.dynheader.make_error_response(
+                            return Some(call.dynheader.make_error_response(
                                 BLUEZ_FAILED.to_string(),
                                 Some("Expected byte array as first parameter.".to_string()),
-                            )
+                            ))
                         }
                     };
                     let dict: HashMap<String, Variant> = match parser.get() {
@@ -154,41 +178,69 @@ impl<'a, 'b, 'c> LocalDesc<'a, 'b, 'c> {
                         Err(e) => match e {
                             UnmarshalError::EndOfMessage => HashMap::new(),
                             _ => {
-                                return call.dynheader.make_error_response(
+                                return Some(call.dynheader.make_error_response(
                                     BLUEZ_FAILED.to_string(),
                                     Some("Expected dict as second parameter.".to_string()),
-                                )
+                                ))
                             }
                         },
                     };
-                    let offset = match dict.get("offset") {
-                        Some(var) => match var.get::<u16>() {
-                            Ok(val) => val,
-                            Err(_) => {
-                                return call.dynheader.make_error_response(
-                                    BLUEZ_FAILED.to_string(),
-                                    Some("Expected type for 'offset' to be  uint16.".to_string()),
-                                )
-                            }
-                        },
-                        None => 0,
-                    } as usize;
+                    let ctx = match RequestCtx::from_dict(&dict) {
+                        Ok(ctx) => ctx,
+                        Err(err) => {
+                            return Some(
+                                call.dynheader
+                                    .make_error_response(BLUEZ_FAILED.to_string(), Some(err)),
+                            )
+                        }
+                    };
+                    if let Some(cb) = &mut base.authorize_callback {
+                        if let Err(err) = cb(AuthReq::Write(&ctx, bytes)) {
+                            return Some(err.to_response(&call.dynheader));
+                        }
+                    }
+                    if ctx.prepare_authorize {
+                        // Bluez is only asking to authorize a Prepare Write request.
+                        // The value is written when the prepared writes are executed.
+                        return Some(call.dynheader.make_response());
+                    }
+                    let offset = ctx.offset as usize;
+                    if bytes.len() + offset > 512 {
+                        return Some(
+                            call.dynheader
+                                .make_error_response(BLUEZ_INVALID_LEN.to_string(), None),
+                        );
+                    }
                     let mut cur_val = base.vf.to_value();
-                    let l = cur_val.len() + offset;
-                    if l > 512 {
-                        return call
-                            .dynheader
-                            .make_error_response(BLUEZ_INVALID_LEN.to_string(), None);
+                    if offset > cur_val.len() {
+                        return Some(
+                            call.dynheader
+                                .make_error_response(BLUEZ_INVALID_OFFSET.to_string(), None),
+                        );
                     }
                     cur_val.update(bytes, offset);
+                    if base.deferred_write.is_some() {
+                        let mut target = self.character.reply_target();
+                        target.desc_uuid = Some(self.uuid.clone());
+                        let base = self.get_desc_base_mut();
+                        let cb = base.deferred_write.as_mut().unwrap();
+                        cb(
+                            &cur_val,
+                            WriteReply::new(Some(call.dynheader.clone()), ctx, target),
+                        );
+                        return None;
+                    }
+                    let base = self.get_desc_base_mut();
                     match &mut base.write_callback {
-                        Some(cb) => match cb(&cur_val[..]) {
+                        Some(cb) => match cb(&cur_val[..], &ctx) {
                             Ok(vf) => {
                                 if let Some(vf) = vf {
                                     base.vf = vf;
                                 }
                             }
-                            Err((s1, s2)) => return call.dynheader.make_error_response(s1, s2),
+                            Err((s1, s2)) => {
+                                return Some(call.dynheader.make_error_response(s1, s2))
+                            }
                         },
                         None => base.vf = ValOrFn::Value(cur_val),
                     }
@@ -203,7 +255,8 @@ impl<'a, 'b, 'c> LocalDesc<'a, 'b, 'c> {
             _ => call
                 .dynheader
                 .make_error_response(UNKNOWN_METHOD.to_string(), None),
-        }
+        };
+        Some(reply)
     }
 }
 
@@ -253,11 +306,31 @@ impl DescFlags {
             ret.push("secure-read".to_string());
         }
         if self.authorize {
-            unimplemented!();
             ret.push("authorize".to_string());
         }
         ret
     }
+    pub(crate) fn from_strings<'a, I>(flags: I) -> DescFlags
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut ret = DescFlags::default();
+        for flag in flags {
+            match flag {
+                "read" => ret.read = true,
+                "write" => ret.write = true,
+                "encrypt-read" => ret.encrypt_read = true,
+                "encrypt-write" => ret.encrypt_write = true,
+                "encrypt-authenticated-read" => ret.encrypt_auth_read = true,
+                "encrypt-authenticated-write" => ret.encrypt_auth_write = true,
+                "secure-write" => ret.secure_write = true,
+                "secure-read" => ret.secure_read = true,
+                "authorize" => ret.authorize = true,
+                _ => (),
+            }
+        }
+        ret
+    }
 }
 
 impl Properties for LocalDescBase {
@@ -354,6 +427,7 @@ pub(crate) struct RemoteDescBase {
     uuid: UUID,
     value: Rc<Cell<AttValue>>,
     path: PathBuf,
+    flags: DescFlags,
 }
 impl RemoteDescBase {
     pub(crate) fn from_props(
@@ -384,13 +458,41 @@ impl RemoteDescBase {
                     ))
                 }
             },
-            None => {
-                return Err(Error::DbusReqErr(
-                    "Invalid descriptor returned; missing Value field".to_string(),
-                ))
-            }
+            // Bluez only includes the Value once it is has been read
+            None => Rc::new(Cell::new(AttValue::default())),
+        };
+        let flags = match props.remove(FLAGS_PROP) {
+            Some(var) => match var.get::<Vec<&str>>() {
+                Ok(flags) => DescFlags::from_strings(flags),
+                Err(_) => {
+                    return Err(Error::DbusReqErr(
+                        "Invalid descriptor returned; Flags field is invalid type".to_string(),
+                    ))
+                }
+            },
+            // Older versions of Bluez don't expose the descriptor flags
+            None => DescFlags::default(),
         };
-        Ok(RemoteDescBase { uuid, value, path })
+        Ok(RemoteDescBase {
+            uuid,
+            value,
+            path,
+            flags,
+        })
+    }
+    pub(crate) fn update_from_changed(
+        &mut self,
+        changed: HashMap<String, Variant>,
+    ) -> Result<(), Error> {
+        for (prop, var) in changed {
+            match prop.as_str() {
+                "UUID" => self.uuid = var.get::<String>()?.to_uuid(),
+                "Value" => self.value.set(var.get()?),
+                "Flags" => self.flags = DescFlags::from_strings(var.get::<Vec<&str>>()?),
+                _ => (),
+            }
+        }
+        Ok(())
     }
 }
 
@@ -416,6 +518,83 @@ impl RemoteDesc<'_, '_, '_, '_> {
             .get(&self.uuid)
             .unwrap()
     }
+    /// Reads the value of the remote descriptor starting at `offset`.
+    ///
+    /// When `offset` is zero, the cached value of the descriptor is updated
+    /// with the result.
+    pub fn read_offset(
+        &mut self,
+        offset: u16,
+    ) -> Result<Pending<Result<AttValue, Error>, Rc<Cell<AttValue>>>, Error> {
+        let base = self.get_desc_base();
+        let data = if offset == 0 {
+            base.value.clone()
+        } else {
+            Rc::new(Cell::new(AttValue::default()))
+        };
+        let mut msg = MessageBuilder::new()
+            .call("ReadValue".to_string())
+            .on(base.path.to_str().unwrap().to_string())
+            .at(BLUEZ_DEST.to_string())
+            .with_interface(DESC_IF_STR.to_string())
+            .build();
+        let mut options = HashMap::new();
+        if offset != 0 {
+            options.insert("offset", CharVar::U16(offset));
+        }
+        msg.body.push_param(options).unwrap();
+        let blue = self.character.get_blue_mut();
+        let res_idx = blue.rpc_con.send_message(&mut msg, Timeout::Infinite)?;
+        Ok(Pending {
+            dbus_res: res_idx,
+            typ: Some(PendingType::MessageCb(&mm_to_descvalue)),
+            data: Some(data),
+            leaking: Rc::downgrade(&blue.leaking),
+        })
+    }
+    /// Reads the value of the remote descriptor starting at `offset` and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn read_offset_wait(&mut self, offset: u16) -> Result<AttValue, Error> {
+        let pend = self.read_offset(offset)?;
+        let blue = self.character.get_blue_mut();
+        blue.resolve(pend).map_err(|e| e.1)?
+    }
+    /// Writes `val` to the remote descriptor starting at `offset`.
+    pub fn write_offset(
+        &mut self,
+        val: AttValue,
+        offset: u16,
+    ) -> Result<Pending<Result<(), Error>, ()>, Error> {
+        let base = self.get_desc_base();
+        let mut msg = MessageBuilder::new()
+            .call("WriteValue".to_string())
+            .on(base.path.to_str().unwrap().to_string())
+            .at(BLUEZ_DEST.to_string())
+            .with_interface(DESC_IF_STR.to_string())
+            .build();
+        let mut options = HashMap::new();
+        if offset != 0 {
+            options.insert("offset", CharVar::U16(offset));
+        }
+        msg.body.push_param2(val.as_slice(), options).unwrap();
+        let blue = self.character.get_blue_mut();
+        let res_idx = blue.rpc_con.send_message(&mut msg, Timeout::Infinite)?;
+        Ok(Pending {
+            dbus_res: res_idx,
+            typ: Some(PendingType::MessageCb(&desc_write_cb)),
+            data: Some(()),
+            leaking: Rc::downgrade(&blue.leaking),
+        })
+    }
+    /// Writes `val` to the remote descriptor starting at `offset` and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn write_offset_wait(&mut self, val: AttValue, offset: u16) -> Result<(), Error> {
+        let pend = self.write_offset(val, offset)?;
+        let blue = self.character.get_blue_mut();
+        blue.resolve(pend).map_err(|e| e.1)?
+    }
 }
 impl AttObject for RemoteDesc<'_, '_, '_, '_> {
     fn path(&self) -> &Path {
@@ -426,3 +605,65 @@ impl AttObject for RemoteDesc<'_, '_, '_, '_> {
         &self.uuid
     }
 }
+impl FlaggedAtt for RemoteDesc<'_, '_, '_, '_> {
+    type Flags = DescFlags;
+    fn flags(&self) -> Self::Flags {
+        self.get_desc_base().flags
+    }
+}
+impl ReadableAtt for RemoteDesc<'_, '_, '_, '_> {
+    /// Reads a value from the remote device's descriptor.
+    fn read(&mut self) -> Result<Pending<Result<AttValue, Error>, Rc<Cell<AttValue>>>, Error> {
+        self.read_offset(0)
+    }
+    fn read_wait(&mut self) -> Result<AttValue, Error> {
+        self.read_offset_wait(0)
+    }
+    /// Returns the value from the last read of the descriptor, or
+    /// the value Bluez had cached when the descriptor was discovered.
+    fn read_cached(&mut self) -> AttValue {
+        self.get_desc_base().value.get()
+    }
+}
+impl WritableAtt for RemoteDesc<'_, '_, '_, '_> {
+    /// Writes a value to the remote device's descriptor.
+    /// Descriptors are always written with a response, so `write_type` is ignored.
+    fn write(
+        &mut self,
+        val: AttValue,
+        _write_type: WriteType,
+    ) -> Result<Pending<Result<(), Error>, ()>, Error> {
+        self.write_offset(val, 0)
+    }
+    fn write_wait(&mut self, val: AttValue, _write_type: WriteType) -> Result<(), Error> {
+        self.write_offset_wait(val, 0)
+    }
+    /// Descriptors cannot be acquired, so this always returns `false`.
+    fn write_acquired(&self) -> bool {
+        false
+    }
+}
+fn mm_to_descvalue(res: MarshalledMessage, data: Rc<Cell<AttValue>>) -> Result<AttValue, Error> {
+    match res.typ {
+        MessageType::Reply => {
+            let buf: AttValue = res.body.parser().get()?;
+            data.set(buf);
+            Ok(buf)
+        }
+        MessageType::Error => Err(Error::DbusReqErr(format!(
+            "Failed to read descriptor: {:?}",
+            res.dynheader.error_name
+        ))),
+        _ => unreachable!(),
+    }
+}
+fn desc_write_cb(res: MarshalledMessage, _: ()) -> Result<(), Error> {
+    match res.typ {
+        MessageType::Reply => Ok(()),
+        MessageType::Error => Err(Error::DbusReqErr(format!(
+            "Failed to write to descriptor: {:?}",
+            res.dynheader.error_name
+        ))),
+        _ => unreachable!(),
+    }
+}
@@ -1,18 +1,24 @@
 //! Module containing structures and traits for interacting with remote
 //! GATT services/characteristics/descriptors and creating local GATT services.
-use crate::{Error, Pending, ToUUID, UUID};
+use crate::interfaces::{
+    BLUEZ_FAILED, BLUEZ_INVALID_LEN, BLUEZ_INVALID_OFFSET, BLUEZ_IN_PROGRESS, BLUEZ_NOT_AUTHORIZED,
+    BLUEZ_NOT_PERM, BLUEZ_NOT_SUPPORTED,
+};
+use crate::{devmac_to_mac, path, Error, Pending, ToUUID, Variant, MAC, UUID};
+use rustbus::message_builder::{DynamicHeader, MarshalledMessage};
 use rustbus::wire::marshal::traits::{Marshal, Signature};
 use rustbus::wire::unmarshal;
 use rustbus::wire::unmarshal::traits::Unmarshal;
 use rustbus::{dbus_variant, ByteOrder};
 use std::borrow::Borrow;
 use std::cell::Cell;
+use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
 use std::mem::MaybeUninit;
 use std::ops::{Deref, DerefMut};
 use std::path::Path;
 use std::rc::Rc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 mod characteristic;
 mod descriptor;
@@ -242,11 +248,270 @@ impl<'a, U: HasChildren<'a>> HasChildren<'a> for &mut U {
     }
 }
 
+/// The context of a read or write request issued by a remote device to a
+/// local characteristic or descriptor.
+///
+/// It is built from the options Bluez passes to `ReadValue`/`WriteValue` and given to
+/// [`ValOrFn::Function`] and the write callbacks. When the value is read by the local application,
+/// such as for notifications, the default context (with no device) is used.
+///
+/// [`ValOrFn::Function`]: ./enum.ValOrFn.html#variant.Function
+#[derive(Clone, Debug, Default)]
+pub struct RequestCtx {
+    /// The MAC address of the remote device issuing the request.
+    pub device: Option<MAC>,
+    /// The ATT MTU negotiated with the remote device.
+    pub mtu: Option<u16>,
+    /// The offset into the value being read or written.
+    pub offset: u16,
+    /// The link type used by the remote device, such as `"BR/EDR"` or `"LE"`.
+    pub link: Option<String>,
+    /// The type of write. This is `None` for reads.
+    pub write_type: Option<WriteType>,
+    /// Set when Bluez is only asking to authorize a Prepare Write request.
+    pub prepare_authorize: bool,
+}
+impl RequestCtx {
+    pub(crate) fn from_dict(dict: &HashMap<String, Variant>) -> Result<Self, String> {
+        let mut ret = RequestCtx::default();
+        if let Some(var) = dict.get("device") {
+            let path = var
+                .get::<&path::ObjectPath>()
+                .map_err(|_| "Expected type for 'device' to be object path.".to_string())?;
+            ret.device = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(devmac_to_mac);
+        }
+        if let Some(var) = dict.get("mtu") {
+            let mtu = var
+                .get::<u16>()
+                .map_err(|_| "Expected type for 'mtu' to be uint16.".to_string())?;
+            ret.mtu = Some(mtu);
+        }
+        if let Some(var) = dict.get("offset") {
+            ret.offset = var
+                .get::<u16>()
+                .map_err(|_| "Expected type for 'offset' to be uint16.".to_string())?;
+        }
+        if let Some(var) = dict.get("link") {
+            let link = var
+                .get::<String>()
+                .map_err(|_| "Expected type for 'link' to be string.".to_string())?;
+            ret.link = Some(link);
+        }
+        if let Some(var) = dict.get("type") {
+            let write_type = var
+                .get::<&str>()
+                .map_err(|_| "Expected type for 'type' to be string.".to_string())?;
+            ret.write_type = Some(
+                WriteType::from_str(write_type)
+                    .ok_or_else(|| format!("Unknown write type: {}", write_type))?,
+            );
+        }
+        if let Some(var) = dict.get("prepare-authorize") {
+            ret.prepare_authorize = var
+                .get::<bool>()
+                .map_err(|_| "Expected type for 'prepare-authorize' to be boolean.".to_string())?;
+        }
+        Ok(ret)
+    }
+}
+
+/// How long Bluez waits for the reply to a `ReadValue` or `WriteValue` call,
+/// which is the default DBus method call timeout.
+const DEFERRED_REPLY_TIMEOUT: Duration = Duration::from_secs(25);
+
+/// The local characteristic or descriptor a deferred reply is for.
+#[derive(Clone, Debug)]
+pub(crate) struct ReplyTarget {
+    pub(crate) serv_uuid: UUID,
+    pub(crate) char_uuid: UUID,
+    pub(crate) desc_uuid: Option<UUID>,
+}
+
+/// A token for replying later to a `ReadValue` call from a remote device,
+/// given to a [`ValOrFn::Deferred`] function.
+///
+/// The read is answered with [`Bluetooth::reply_read()`]. Meanwhile, [`Bluetooth::process_requests()`]
+/// continues serving other requests. If the token is dropped without replying, Bluez times out the request.
+///
+/// [`ValOrFn::Deferred`]: ./enum.ValOrFn.html#variant.Deferred
+/// [`Bluetooth::reply_read()`]: ../struct.Bluetooth.html#method.reply_read
+/// [`Bluetooth::process_requests()`]: ../struct.Bluetooth.html#method.process_requests
+#[derive(Debug)]
+pub struct ReadReply {
+    pub(crate) call: DynamicHeader,
+    pub(crate) ctx: RequestCtx,
+    pub(crate) deadline: Instant,
+}
+impl ReadReply {
+    pub(crate) fn new(call: DynamicHeader, ctx: RequestCtx) -> Self {
+        ReadReply {
+            call,
+            ctx,
+            deadline: Instant::now() + DEFERRED_REPLY_TIMEOUT,
+        }
+    }
+    /// Returns the context of the read request.
+    pub fn ctx(&self) -> &RequestCtx {
+        &self.ctx
+    }
+    /// Checks if Bluez has already given up waiting for the reply.
+    pub fn expired(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+}
+
+/// A token for replying later to a `WriteValue` call from a remote device,
+/// given to the `deferred_write` callback of local characteristics and descriptors.
+///
+/// The write is answered with [`Bluetooth::reply_write()`]. Meanwhile, [`Bluetooth::process_requests()`]
+/// continues serving other requests. If the token is dropped without replying, Bluez times out the request.
+///
+/// [`Bluetooth::reply_write()`]: ../struct.Bluetooth.html#method.reply_write
+/// [`Bluetooth::process_requests()`]: ../struct.Bluetooth.html#method.process_requests
+#[derive(Debug)]
+pub struct WriteReply {
+    /// `None` for reliable writes, which Bluez has already responded to.
+    pub(crate) call: Option<DynamicHeader>,
+    pub(crate) ctx: RequestCtx,
+    pub(crate) target: ReplyTarget,
+    pub(crate) deadline: Instant,
+}
+impl WriteReply {
+    pub(crate) fn new(call: Option<DynamicHeader>, ctx: RequestCtx, target: ReplyTarget) -> Self {
+        WriteReply {
+            call,
+            ctx,
+            target,
+            deadline: Instant::now() + DEFERRED_REPLY_TIMEOUT,
+        }
+    }
+    /// Returns the context of the write request.
+    pub fn ctx(&self) -> &RequestCtx {
+        &self.ctx
+    }
+    /// Checks if Bluez has already given up waiting for the reply.
+    pub fn expired(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+}
+
+/// Errors that handlers of local characteristics and descriptors can return to remote devices.
+///
+/// They are sent to Bluez as the corresponding `org.bluez.Error.*` DBus errors, which
+/// Bluez translates into ATT error codes.
+/// It can also be converted into the `(String, Option<String>)` errors returned by write callbacks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AttError {
+    /// The attribute can't be read or written.
+    NotPermitted,
+    /// The remote device is not authorized to read or write the attribute.
+    NotAuthorized,
+    /// The offset of the read or write is past the end of the value.
+    InvalidOffset,
+    /// The value being written has an invalid length.
+    InvalidValueLength,
+    /// The request is not supported by the attribute.
+    NotSupported,
+    /// Another operation on the attribute is in progress.
+    InProgress,
+    /// A general failure, such as the source of the value being unavailable,
+    /// with an optional error message.
+    Failed(Option<String>),
+}
+impl AttError {
+    /// Returns the DBus error name of the error.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AttError::NotPermitted => BLUEZ_NOT_PERM,
+            AttError::NotAuthorized => BLUEZ_NOT_AUTHORIZED,
+            AttError::InvalidOffset => BLUEZ_INVALID_OFFSET,
+            AttError::InvalidValueLength => BLUEZ_INVALID_LEN,
+            AttError::NotSupported => BLUEZ_NOT_SUPPORTED,
+            AttError::InProgress => BLUEZ_IN_PROGRESS,
+            AttError::Failed(_) => BLUEZ_FAILED,
+        }
+    }
+    pub(crate) fn to_response(self, call: &DynamicHeader) -> MarshalledMessage {
+        let (name, msg) = self.into();
+        call.make_error_response(name, msg)
+    }
+}
+impl From<AttError> for (String, Option<String>) {
+    fn from(err: AttError) -> Self {
+        let name = err.name().to_string();
+        match err {
+            AttError::Failed(msg) => (name, msg),
+            _ => (name, None),
+        }
+    }
+}
+
+/// A request from a remote device that is given to the `authorize_callback`
+/// of local characteristics and descriptors to be allowed or denied.
+#[derive(Clone, Copy, Debug)]
+pub enum AuthReq<'a> {
+    /// A read of the attribute's value.
+    Read(&'a RequestCtx),
+    /// A write of the given bytes to the attribute.
+    /// If [`RequestCtx::prepare_authorize`] is set, this is a Prepare Write request that
+    /// is written later, if the remote device executes it.
+    ///
+    /// [`RequestCtx::prepare_authorize`]: ./struct.RequestCtx.html#structfield.prepare_authorize
+    Write(&'a RequestCtx, &'a [u8]),
+}
+
+/// A change in whether a remote device is subscribed to the notifications or indications
+/// of a local characteristic. It is given to the `subscribe_callback` of local characteristics.
+///
+/// Bluez only gives the device and MTU with `AcquireNotify`, so the [`RequestCtx`] is
+/// the default for subscriptions made through `StartNotify`.
+///
+/// [`RequestCtx`]: ./struct.RequestCtx.html
+#[derive(Clone, Debug)]
+pub enum SubscribeEvent {
+    /// A remote device subscribed with `StartNotify` (`acquired` is `false`)
+    /// or with `AcquireNotify` (`acquired` is `true`).
+    Subscribed { ctx: RequestCtx, acquired: bool },
+    /// A remote device unsubscribed with `StopNotify`.
+    Unsubscribed(RequestCtx),
+    /// The notify socket from `AcquireNotify` was closed, such as when
+    /// the remote device unsubscribed or disconnected.
+    SocketClosed(RequestCtx),
+}
+
+/// The outcome of an indication sent with [`LocalChar::indicate()`].
+///
+/// [`LocalChar::indicate()`]: ./struct.LocalChar.html#method.indicate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndicateStatus {
+    /// A remote device confirmed the indication.
+    Confirmed,
+    /// The indication wasn't confirmed before the timeout.
+    TimedOut,
+    /// The remote devices unsubscribed before the indication was sent or confirmed.
+    Cancelled,
+    /// The indication couldn't be sent to Bluez.
+    Failed,
+}
+
 /// Use to set the value of a local characteristic or descriptor.
 /// The value can be an actual value or it can be callback that returns value.
+/// The callback is given the context of the read request.
 pub enum ValOrFn {
     Value(AttValue),
-    Function(Box<dyn FnMut() -> AttValue>),
+    Function(Box<dyn FnMut(&RequestCtx) -> AttValue>),
+    /// A callback that can fail, such as when the source of the value is unavailable.
+    /// When read by a remote device, the error is returned to it.
+    TryFunction(Box<dyn FnMut(&RequestCtx) -> Result<AttValue, AttError>>),
+    /// A callback that takes ownership of reads from remote devices, so they can be
+    /// answered later with [`Bluetooth::reply_read()`].
+    /// Local reads of the value return an empty value, and notifications are not sent.
+    ///
+    /// [`Bluetooth::reply_read()`]: ../struct.Bluetooth.html#method.reply_read
+    Deferred(Box<dyn FnMut(ReadReply)>),
 }
 impl Default for ValOrFn {
     fn default() -> Self {
@@ -266,20 +531,41 @@ impl<T: AsRef<AttValue>> From<T> for ValOrFn {
 
 impl Debug for ValOrFn {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if let ValOrFn::Value(cv) = self {
-            write!(f, "ValOrFn {{ Value: {:?} }}", cv)
-        } else {
-            write!(f, "ValOrFn {{ Fn  }}")
+        match self {
+            ValOrFn::Value(cv) => write!(f, "ValOrFn {{ Value: {:?} }}", cv),
+            ValOrFn::Function(_) => write!(f, "ValOrFn {{ Fn  }}"),
+            ValOrFn::TryFunction(_) => write!(f, "ValOrFn {{ TryFn  }}"),
+            ValOrFn::Deferred(_) => write!(f, "ValOrFn {{ Deferred  }}"),
         }
     }
 }
 
 impl ValOrFn {
+    /// Gets the value, calling the function with the default context if it is one.
     #[inline]
     pub fn to_value(&mut self) -> AttValue {
+        self.to_value_with_ctx(&RequestCtx::default())
+    }
+    /// Gets the value, calling the function with `ctx` if it is one.
+    ///
+    /// If the value is given by a [`ValOrFn::TryFunction`] that fails, an empty value is returned.
+    ///
+    /// [`ValOrFn::TryFunction`]: ./enum.ValOrFn.html#variant.TryFunction
+    #[inline]
+    pub fn to_value_with_ctx(&mut self, ctx: &RequestCtx) -> AttValue {
+        self.try_to_value_with_ctx(ctx).unwrap_or_default()
+    }
+    /// Gets the value, calling the function with `ctx` if it is one,
+    /// and returning the error of a failed [`ValOrFn::TryFunction`].
+    ///
+    /// [`ValOrFn::TryFunction`]: ./enum.ValOrFn.html#variant.TryFunction
+    #[inline]
+    pub fn try_to_value_with_ctx(&mut self, ctx: &RequestCtx) -> Result<AttValue, AttError> {
         match self {
-            ValOrFn::Value(cv) => (*cv),
-            ValOrFn::Function(f) => f(),
+            ValOrFn::Value(cv) => Ok(*cv),
+            ValOrFn::Function(f) => Ok(f(ctx)),
+            ValOrFn::TryFunction(f) => f(ctx),
+            ValOrFn::Deferred(_) => Err(AttError::NotSupported),
         }
     }
     pub fn from_slice(slice: &[u8]) -> Self {
@@ -421,6 +707,7 @@ impl Marshal for AttValue {
 #[cfg(test)]
 mod tests {
     use crate::gatt::{match_char, match_serv};
+    use crate::gatt::{AttError, AttValue, RequestCtx, ValOrFn};
     use crate::gatt::{CharFlags, DescFlags, LocalCharBase, LocalDescBase, LocalServiceBase};
     use crate::ToUUID;
     use std::path::{Path, PathBuf};
@@ -477,4 +764,28 @@ mod tests {
             None
         );
     }
+    #[test]
+    fn test_try_function() {
+        let mut vf = ValOrFn::TryFunction(Box::new(|ctx| {
+            if ctx.offset == 0 {
+                Ok(AttValue::from(&[1, 2, 3][..]))
+            } else {
+                Err(AttError::Failed(Some("Sensor unavailable".to_string())))
+            }
+        }));
+        let mut ctx = RequestCtx::default();
+        assert_eq!(
+            vf.try_to_value_with_ctx(&ctx).unwrap().as_slice(),
+            &[1, 2, 3]
+        );
+        ctx.offset = 1;
+        let err = vf.try_to_value_with_ctx(&ctx).unwrap_err();
+        assert_eq!(vf.to_value_with_ctx(&ctx).len(), 0);
+        let (name, msg): (String, Option<String>) = err.into();
+        assert_eq!(name, "org.bluez.Error.Failed");
+        assert_eq!(msg.as_deref(), Some("Sensor unavailable"));
+        let (name, msg): (String, Option<String>) = AttError::NotAuthorized.into();
+        assert_eq!(name, "org.bluez.Error.NotAuthorized");
+        assert_eq!(msg, None);
+    }
 }
@@ -8,7 +8,8 @@ use std::path::{Path, PathBuf};
 pub trait Service<'a> {
     /// Return in a service is primary service.
     fn primary(&self) -> bool;
-    fn includes(&self) -> &[&Path];
+    /// Returns the `UUID`s of the sibling services included by this service.
+    fn includes(&self) -> &[UUID];
 }
 /// `LocalServiceBase` is used to construct local service to be provided by the local GATT server.
 pub struct LocalServiceBase {
@@ -19,6 +20,8 @@ pub struct LocalServiceBase {
     pub(crate) path: PathBuf,
     pub(crate) chars: HashMap<UUID, LocalCharBase>,
     primary: bool,
+    includes: Vec<UUID>,
+    pub(crate) include_paths: Vec<PathBuf>,
 }
 impl LocalServiceBase {
     /// Construct a new `LocalServiceBase` to construct as service with.
@@ -37,8 +40,31 @@ impl LocalServiceBase {
             path: PathBuf::new(),
             chars: HashMap::new(),
             primary,
+            includes: Vec::new(),
+            include_paths: Vec::new(),
         }
     }
+    /// Include another service in this service. The included service must also be added to
+    /// the same `Bluetooth` instance, before [`Bluetooth::register_application()`] is called.
+    ///
+    /// [`Bluetooth::register_application()`]: ../struct.Bluetooth.html#method.register_application
+    pub fn add_include<T: ToUUID>(&mut self, uuid: T) -> Result<(), Error> {
+        let uuid = uuid.to_uuid();
+        if !validate_uuid(&uuid) {
+            return Err(Error::BadInput(format!(
+                "{} is an invalid uuid for an included service",
+                uuid
+            )));
+        }
+        if !self.includes.contains(&uuid) {
+            self.includes.push(uuid);
+        }
+        Ok(())
+    }
+    /// Returns the `UUID`s of the services included by this service.
+    pub fn includes(&self) -> &[UUID] {
+        &self.includes
+    }
     /// Add a characteristic to the service
     pub fn add_char(&mut self, mut character: LocalCharBase) {
         // TODO: add check for duplicate UUIDs
@@ -111,8 +137,8 @@ impl<'a, 'b: 'a, 'c: 'a, 'd: 'a> Service<'a> for LocalService<'b> {
         let service = self.get_service_base();
         service.primary
     }
-    fn includes(&self) -> &[&Path] {
-        unimplemented!()
+    fn includes(&self) -> &[UUID] {
+        &self.get_service_base().includes
     }
 }
 impl<'a, 'b: 'a> HasChildren<'a> for LocalService<'b> {
@@ -175,6 +201,19 @@ impl Properties for LocalServiceBase {
                     // eprintln!("Getting handle: {}", self.index);
                     Some(base_param_to_variant(Base::Uint16(self.handle)))
                 }
+                INCLUDES_PROP => {
+                    let paths: Vec<Param<'a, 'b>> = self
+                        .include_paths
+                        .iter()
+                        .map(|p| Param::Base(Base::ObjectPath(p.to_str().unwrap().to_string())))
+                        .collect();
+                    Some(container_param_to_variant(Container::Array(
+                        params::Array {
+                            element_sig: signature::Type::Base(signature::Base::ObjectPath),
+                            values: paths,
+                        },
+                    )))
+                }
                 _ => None,
             },
             PROP_IF_STR => None,
@@ -226,6 +265,10 @@ pub(crate) struct RemoteServiceBase {
     primary: bool,
     path: PathBuf,
     pub(crate) chars: HashMap<UUID, RemoteCharBase>,
+    /// The paths of the included services, as given by Bluez.
+    include_paths: Vec<PathBuf>,
+    /// The `UUID`s of the included services, resolved from `include_paths`.
+    includes: Vec<UUID>,
 }
 impl RemoteServiceBase {
     pub(crate) fn from_props(
@@ -262,13 +305,44 @@ impl RemoteServiceBase {
                 ))
             }
         };
+        let include_paths = match value.remove(INCLUDES_PROP) {
+            Some(includes) => match includes.get::<Vec<&path::ObjectPath>>() {
+                Ok(includes) => includes.into_iter().map(|p| p.to_path_buf()).collect(),
+                Err(_) => {
+                    return Err(Error::DbusReqErr(
+                        "Invalid service returned; Includes is invalid type".to_string(),
+                    ))
+                }
+            },
+            None => Vec::new(),
+        };
         Ok(RemoteServiceBase {
             uuid,
             primary,
             path,
             chars: HashMap::new(),
+            include_paths,
+            includes: Vec::new(),
         })
     }
+    /// Resolves the paths of the services included by the service `uuid` to their `UUID`s.
+    /// Included services that are not known by the device are skipped.
+    pub(crate) fn resolve_includes(services: &mut HashMap<UUID, RemoteServiceBase>, uuid: &UUID) {
+        let includes = match services.get(uuid) {
+            Some(serv) => serv
+                .include_paths
+                .iter()
+                .filter_map(|inc| {
+                    services
+                        .values()
+                        .find(|serv| &serv.path == inc)
+                        .map(|serv| serv.uuid.clone())
+                })
+                .collect(),
+            None => return,
+        };
+        services.get_mut(uuid).unwrap().includes = includes;
+    }
     pub(crate) fn update_from_changed(
         &mut self,
         changed: HashMap<String, Variant>,
@@ -277,13 +351,22 @@ impl RemoteServiceBase {
             match prop.as_str() {
                 "Primary" => self.primary = var.get()?,
                 "UUID" => self.uuid = var.get::<String>()?.to_uuid(),
+                "Includes" => {
+                    self.include_paths = var
+                        .get::<Vec<&path::ObjectPath>>()?
+                        .into_iter()
+                        .map(|p| p.to_path_buf())
+                        .collect()
+                }
                 _ => (),
             }
         }
         Ok(())
     }
+    /// Updates the service from the full set of its properties,
+    /// such as those given by `InterfacesAdded`.
     pub(crate) fn update_all(&mut self, props: HashMap<String, Variant>) -> Result<(), Error> {
-        unimplemented!()
+        self.update_from_changed(props)
     }
 }
 impl AttObject for RemoteServiceBase {
@@ -295,18 +378,95 @@ impl AttObject for RemoteServiceBase {
     }
 }
 impl<'a> HasChildren<'a> for RemoteServiceBase {
-    type Child = RemoteCharBase;
+    type Child = &'a mut RemoteCharBase;
     fn get_children(&self) -> Vec<UUID> {
         self.chars.keys().map(|x| x.clone()).collect()
     }
     fn get_child<T: ToUUID>(&'a mut self, uuid: T) -> Option<Self::Child> {
-        unimplemented!()
+        let uuid = uuid.to_uuid();
+        self.chars.get_mut(&uuid)
     }
 }
+/// Creates a `RemoteServiceBase` from an `InterfacesAdded` signal for a remote service.
+/// Its characteristics are not populated.
 impl TryFrom<&Message<'_, '_>> for RemoteServiceBase {
     type Error = Error;
-    fn try_from(_value: &Message) -> Result<Self, Self::Error> {
-        unimplemented!()
+    fn try_from(value: &Message) -> Result<Self, Self::Error> {
+        let path = match value.params.get(0) {
+            Some(Param::Base(Base::ObjectPath(path))) => PathBuf::from(path),
+            _ => {
+                return Err(Error::DbusReqErr(
+                    "Invalid service message; expected object path as first parameter".to_string(),
+                ))
+            }
+        };
+        let props = match value.params.get(1) {
+            Some(Param::Container(Container::Dict(i_and_p))) => {
+                match i_and_p.map.get(&Base::String(SERV_IF_STR.to_string())) {
+                    Some(Param::Container(Container::Dict(props))) => props,
+                    _ => {
+                        return Err(Error::DbusReqErr(format!(
+                            "Invalid service message; missing {} interface",
+                            SERV_IF_STR
+                        )))
+                    }
+                }
+            }
+            _ => {
+                return Err(Error::DbusReqErr(
+                    "Invalid service message; expected dict as second parameter".to_string(),
+                ))
+            }
+        };
+        let get_prop = |prop: &str| match props.map.get(&Base::String(prop.to_string())) {
+            Some(Param::Container(Container::Variant(var))) => Some(&var.value),
+            _ => None,
+        };
+        let uuid = match get_prop(UUID_PROP) {
+            Some(Param::Base(Base::String(uuid))) => uuid.to_uuid(),
+            Some(_) => {
+                return Err(Error::DbusReqErr(
+                    "Invalid service returned; UUID is invalid type".to_string(),
+                ))
+            }
+            None => {
+                return Err(Error::DbusReqErr(
+                    "Invalid service returned; missing UUID field".to_string(),
+                ))
+            }
+        };
+        let include_paths = match get_prop(INCLUDES_PROP) {
+            Some(Param::Container(Container::Array(includes))) => includes
+                .values
+                .iter()
+                .filter_map(|p| match p {
+                    Param::Base(Base::ObjectPath(p)) => Some(PathBuf::from(p)),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let primary = match get_prop(PRIMARY_PROP) {
+            Some(Param::Base(Base::Boolean(primary))) => *primary,
+            Some(_) => {
+                return Err(Error::DbusReqErr(
+                    "Invalid service returned; Primary is invalid type".to_string(),
+                ))
+            }
+            None => {
+                return Err(Error::DbusReqErr(
+                    "Invalid service returned; missing Primary field".to_string(),
+                ))
+            }
+        };
+        Ok(RemoteServiceBase {
+            uuid,
+            primary,
+            path,
+            chars: HashMap::new(),
+            include_paths,
+            includes: Vec::new(),
+        })
     }
 }
 /// Return type for [`RemoteDevice::get_service()`]. It represents a service on another device.
@@ -346,9 +506,12 @@ impl<'a, 'b: 'a, 'c: 'a> Service<'a> for RemoteService<'b, 'c> {
     fn primary(&self) -> bool {
         self.get_service_base().primary
     }
-    /// **unimplemented**
-    fn includes(&self) -> &[&Path] {
-        unimplemented!()
+    /// Included services that were not known by the device when the service was
+    /// retrieved with [`RemoteDevice::get_service()`] are skipped.
+    ///
+    /// [`RemoteDevice::get_service()`]: ../struct.RemoteDevice.html#method.get_service
+    fn includes(&self) -> &[UUID] {
+        &self.get_service_base().includes
     }
 }
 impl AttObject for RemoteService<'_, '_> {
@@ -13,6 +13,7 @@ pub const DEV_IF_STR: &'static str = "org.bluez.Device1";
 pub const MANAGER_IF_STR: &'static str = "org.bluez.GattManager1";
 pub const LEAD_IF_STR: &'static str = "org.bluez.LEAdvertisement1";
 pub const INTRO_IF_STR: &'static str = "org.freedesktop.DBus.Introspectable";
+pub const PEER_IF_STR: &'static str = "org.freedesktop.DBus.Peer";
 pub const ADAPTER_IF_STR: &'static str = "org.bluez.Adapter1";
 
 pub const UUID_PROP: &'static str = "UUID";
@@ -28,6 +29,7 @@ pub const PRIMARY_PROP: &'static str = "Primary";
 pub const DEVICE_PROP: &'static str = "Device";
 pub const INCLUDES_PROP: &'static str = "Includes";
 pub const TYPE_PROP: &'static str = "Type";
+pub const MTU_PROP: &'static str = "MTU";
 pub const SERV_UUIDS_PROP: &'static str = "ServiceUUIDs";
 pub const SOLICIT_UUIDS_PROP: &'static str = "SolicitUUIDs";
 pub const SERV_DATA_PROP: &'static str = "ServiceData";
@@ -42,7 +44,7 @@ pub const TO_PROP: &'static str = "Timeout";
 pub const SND_CHANNEL_PROP: &'static str = "SecondaryChannel";
 
 pub(crate) const SERV_IF_PROPS: &[&'static str] =
-    &[UUID_PROP, PRIMARY_PROP, DEVICE_PROP, HANDLE_PROP]; // HANDLE_PROP is not used
+    &[UUID_PROP, PRIMARY_PROP, DEVICE_PROP, HANDLE_PROP, INCLUDES_PROP]; // HANDLE_PROP is not used
 pub(crate) const CHAR_IF_PROPS: &[&'static str] = &[
     UUID_PROP,
     SERVICE_PROP,
@@ -90,12 +92,16 @@ pub const GET_ALL_CALL: &'static str = "GetAll";
 pub const BLUEZ_NOT_PERM: &'static str = "org.bluez.Error.NotPermitted";
 pub const BLUEZ_FAILED: &'static str = "org.bluez.Error.Failed";
 pub const BLUEZ_INVALID_LEN: &'static str = "org.bluez.Error.InvalidValueLength";
+pub const BLUEZ_INVALID_OFFSET: &'static str = "org.bluez.Error.InvalidOffset";
+pub const BLUEZ_NOT_AUTHORIZED: &'static str = "org.bluez.Error.NotAuthorized";
+pub const BLUEZ_NOT_SUPPORTED: &'static str = "org.bluez.Error.NotSupported";
+pub const BLUEZ_IN_PROGRESS: &'static str = "org.bluez.Error.InProgress";
 
 // Standard DBus Errors
 pub const UNKNOWN_METHOD: &'static str = "org.dbus.freedesktop.UnknownMethod";
 
-pub const IF_ADDED_SIG: &'static str = "InterfaceAdded";
-pub const IF_REMOVED_SIG: &'static str = "InterfaceRemoved";
+pub const IF_ADDED_SIG: &'static str = "InterfacesAdded";
+pub const IF_REMOVED_SIG: &'static str = "InterfacesRemoved";
 pub const NAME_LOST_SIG: &'static str = "NameLost";
 pub const NAME_OWNER_CHANGED: &'static str = "NameOwnerChanged";
 
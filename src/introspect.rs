@@ -52,6 +52,7 @@ pub(crate) const SERVICE_STR: &'static str = "\t<interface name=\"org.bluez.Gatt
 \t\t<property name=\"Primary\" type=\"b\" access=\"read\"/>
 \t\t<property name=\"Device\" type=\"o\" access=\"read\"/>
 \t\t<property name=\"Handle\" type=\"q\" access=\"read\"/>
+\t\t<property name=\"Includes\" type=\"ao\" access=\"read\"/>
 \t</interface>\n";
 pub(crate) const CHAR_STR: &'static str = "\t<interface name=\"org.bluez.GattCharacteristic1\">
 \t\t<method name=\"ReadValue\">
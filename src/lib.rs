@@ -16,6 +16,8 @@
 //! - Write-without-response via sockets from remote devices (AcquireWrite).
 //! - Notifying/Indicating local characteristics with sockets (AcquireNotify).
 //! - Reading local descriptors from remote devices.
+//! - Authorizing reads and writes of local attributes (`authorize` flag).
+//! - Tracking confirmations of indications from local characteristics.
 //!
 //!  **To Do:**
 //! - Writable descriptors.
@@ -25,9 +27,7 @@
 //! - Writing to remote characteristics.
 //! - Write-without-response via sockets to remote devices (AcquireWrite).
 //! - Receiving remote notification/indications with sockets.
-//!
-//!  **To Do:**
-//! - Descriptors as a client.
+//! - Reading from and writing to remote descriptors.
 //! ## Development status
 //! This library is unstable in *alpha*. There are planned functions
 //! in the API that have yet to be implemented. Unimplemented function are noted.
@@ -115,10 +115,16 @@ pub enum ResolveError<T: 'static, U: 'static> {
 
 pub mod interfaces;
 
+mod adapter;
+pub use adapter::*;
 mod advertisement;
 pub use advertisement::*;
 mod device;
 pub use device::*;
+mod discovery;
+pub use discovery::*;
+mod event;
+pub use event::*;
 
 use interfaces::*;
 pub mod gatt;
@@ -308,11 +314,52 @@ pub struct Bluetooth {
     discoverable: Rc<Cell<bool>>,
     leaking: Rc<RefCell<VecDeque<(u32, Box<dyn FnOnce(MarshalledMessage)>)>>>,
     addr: MAC,
+    addr_type: AddrType,
+    adapter_name: String,
+    alias: Rc<RefCell<String>>,
+    class: u32,
+    pairable: Rc<Cell<bool>>,
+    pairable_timeout: Rc<Cell<u32>>,
+    discoverable_timeout: Rc<Cell<u32>>,
+    discovering: bool,
+    uuids: Vec<UUID>,
+    modalias: Option<String>,
+    roles: Vec<String>,
+    events: VecDeque<BluetoothEvent>,
 }
 
 impl Bluetooth {
     /// Creates a new `Bluetooth` and setup a DBus client to interact with Bluez.
+    /// `blue_path` is the DBus path of the adapter to use, such as `/org/bluez/hci0`.
     pub fn new(dbus_name: String, blue_path: String) -> Result<Self, Error> {
+        let mut ret = Self::connect(dbus_name, blue_path.as_ref())?;
+        ret.setup_match()?;
+        ret.update_adapter_props()?;
+        Ok(ret)
+    }
+    /// Creates a new `Bluetooth` using the local adapter with the address `addr`.
+    ///
+    /// This can be used instead of [`new()`] when the DBus path of the adapter isn't stable,
+    /// such as with USB dongles.
+    ///
+    /// [`new()`]: ./struct.Bluetooth.html#method.new
+    pub fn new_with_addr(dbus_name: String, addr: &MAC) -> Result<Self, Error> {
+        let mut ret = Self::connect(dbus_name, ADAPTER_ROOT.as_ref())?;
+        let info = match ret.adapters()?.into_iter().find(|info| &info.addr == addr) {
+            Some(info) => info,
+            None => {
+                return Err(Error::Bluez(format!(
+                    "No adapter with address {} was found",
+                    addr
+                )))
+            }
+        };
+        ret.blue_path = info.path.as_path().into();
+        ret.setup_match()?;
+        ret.update_adapter_props()?;
+        Ok(ret)
+    }
+    fn connect(dbus_name: String, blue_path: &Path) -> Result<Self, Error> {
         let session_path = get_system_bus_path()?;
         let conn = Conn::connect_to_bus(session_path, true)?;
         let mut rpc_con = RpcConn::new(conn);
@@ -334,7 +381,6 @@ impl Bluetooth {
         path.push_str(&dbus_name.replace(".", "/"));
         let path = PathBuf::from(path);
 
-        let blue_path: &Path = blue_path.as_ref();
         let mut ret = Bluetooth {
             rpc_con,
             name: dbus_name,
@@ -353,6 +399,18 @@ impl Bluetooth {
             powered: Rc::new(Cell::new(false)),
             discoverable: Rc::new(Cell::new(false)),
             addr: "00:00:00:00:00:00".into(),
+            addr_type: AddrType::Public,
+            adapter_name: String::new(),
+            alias: Rc::new(RefCell::new(String::new())),
+            class: 0,
+            pairable: Rc::new(Cell::new(false)),
+            pairable_timeout: Rc::new(Cell::new(0)),
+            discoverable_timeout: Rc::new(Cell::new(0)),
+            discovering: false,
+            uuids: Vec::new(),
+            modalias: None,
+            roles: Vec::new(),
+            events: VecDeque::new(),
         };
         ret.rpc_con.set_filter(Box::new(move |msg| match msg.typ {
             MessageType::Call => true,
@@ -362,28 +420,88 @@ impl Bluetooth {
             MessageType::Signal => true,
         }));
         ret.set_filter(Some(BLUEZ_DEST.to_string()))?;
-        ret.setup_match()?;
-        ret.update_adapter_props()?;
         Ok(ret)
     }
+    /// Lists every local adapter known to Bluez.
+    ///
+    /// **Calls process_requests()**
+    pub fn adapters(&mut self) -> Result<Vec<AdapterInfo>, Error> {
+        let mut msg = MessageBuilder::new()
+            .call(MANGAGED_OBJ_CALL.to_string())
+            .at(BLUEZ_DEST.to_string())
+            .on("/".to_string())
+            .with_interface(OBJ_MANAGER_IF_STR.to_string())
+            .build();
+        let res_idx = self.rpc_con.send_message(&mut msg, Timeout::Infinite)?;
+        loop {
+            self.process_requests()?;
+            if let Some(res) = self.rpc_con.try_get_response(res_idx) {
+                if let MessageType::Error = res.typ {
+                    return Err(Error::DbusReqErr(format!(
+                        "Failed to get adapters: {:?}",
+                        res
+                    )));
+                }
+                let path_map: HashMap<
+                    path::ObjectPathBuf,
+                    HashMap<String, HashMap<String, Variant>>,
+                > = res.body.parser().get()?;
+                let mut ret = Vec::new();
+                for (path, mut if_map) in path_map {
+                    if let Some(props) = if_map.remove(ADAPTER_IF_STR) {
+                        ret.push(AdapterInfo::from_props(props, path.into())?);
+                    }
+                }
+                ret.sort_by(|a, b| a.path.cmp(&b.path));
+                return Ok(ret);
+            }
+        }
+    }
+    /// Get the DBus path of the local adapter being used, such as `/org/bluez/hci0`.
+    pub fn adapter_path(&self) -> &Path {
+        &self.blue_path
+    }
+    /// Get the next event queued by [`process_requests()`], if there is one.
+    /// Applications should drain the queue after each call to `process_requests()`.
+    ///
+    /// If the adapter in use by this `Bluetooth` is removed and later readded
+    /// (at the same path) its cached state is refreshed automatically.
+    ///
+    /// [`process_requests()`]: ./struct.Bluetooth.html#method.process_requests
+    pub fn pop_event(&mut self) -> Option<BluetoothEvent> {
+        self.events.pop_front()
+    }
+    /// Removes and returns all of the queued events.
+    pub fn drain_events(&mut self) -> std::collections::vec_deque::Drain<BluetoothEvent> {
+        self.events.drain(..)
+    }
     fn setup_match(&mut self) -> Result<(), Error> {
-        let match_str = format!(
+        let prop_match = format!(
             "sender='{}',path_namespace='{}',type='signal',",
             BLUEZ_DEST,
             self.blue_path.to_str().unwrap()
         );
-        eprintln!("{}", match_str); // TODO remvoe
-        let mut msg = standard_messages::add_match(match_str);
-        let res_idx = self.rpc_con.send_message(&mut msg, Timeout::Infinite)?;
-        let res = self.rpc_con.wait_response(res_idx, Timeout::Infinite)?;
-        match res.typ {
-            MessageType::Reply => Ok(()),
-            MessageType::Error => Err(Error::DbusReqErr(format!(
-                "Error adding match: {}",
-                res.dynheader.error_name.unwrap()
-            ))),
-            _ => unreachable!(),
+        // InterfacesAdded/InterfacesRemoved are emitted by Bluez's ObjectManager on the root path
+        let obj_match = format!(
+            "sender='{}',interface='{}',path='/',type='signal',",
+            BLUEZ_DEST, OBJ_MANAGER_IF_STR
+        );
+        for match_str in [prop_match, obj_match].iter() {
+            let mut msg = standard_messages::add_match(match_str.clone());
+            let res_idx = self.rpc_con.send_message(&mut msg, Timeout::Infinite)?;
+            let res = self.rpc_con.wait_response(res_idx, Timeout::Infinite)?;
+            match res.typ {
+                MessageType::Reply => (),
+                MessageType::Error => {
+                    return Err(Error::DbusReqErr(format!(
+                        "Error adding match: {}",
+                        res.dynheader.error_name.unwrap()
+                    )))
+                }
+                _ => unreachable!(),
+            }
         }
+        Ok(())
     }
     /// Forces an update of the local cached adapter state by reading the
     /// properties using a Dbus message.
@@ -452,23 +570,119 @@ impl Bluetooth {
         self.powered.replace(powered);
         self.discoverable.replace(discoverable);
         self.addr = addr;
-        Ok(())
+        // Modalias is optional, so it is cleared in case it has been removed
+        self.modalias = None;
+        self.update_from_changed(blue_props)
     }
     fn update_from_changed(&mut self, blue_props: HashMap<String, Variant>) -> Result<(), Error> {
         for (prop, var) in blue_props {
             match prop.as_str() {
                 "Powered" => self.powered.set(var.get()?),
-                "Address" => self.addr = var.get::<String>()?.to_uuid(),
+                "Address" => self.addr = var.get::<String>()?.to_mac(),
+                "AddressType" => {
+                    let addr_type: String = var.get()?;
+                    self.addr_type = AddrType::from_str(&addr_type).ok_or_else(|| {
+                        Error::DbusReqErr(format!(
+                            "Invalid adapter returned; unknown AddressType: {}",
+                            addr_type
+                        ))
+                    })?;
+                }
+                "Name" => self.adapter_name = var.get()?,
+                "Alias" => {
+                    self.alias.replace(var.get()?);
+                }
+                "Class" => self.class = var.get()?,
                 "Discoverable" => self.discoverable.set(var.get()?),
+                "DiscoverableTimeout" => self.discoverable_timeout.set(var.get()?),
+                "Pairable" => self.pairable.set(var.get()?),
+                "PairableTimeout" => self.pairable_timeout.set(var.get()?),
+                "Discovering" => self.discovering = var.get()?,
+                "UUIDs" => {
+                    let uuids: Vec<String> = var.get()?;
+                    self.uuids = uuids.into_iter().map(|uuid| uuid.to_uuid()).collect();
+                }
+                "Modalias" => self.modalias = Some(var.get()?),
+                "Roles" => self.roles = var.get()?,
                 _ => (),
             }
         }
         Ok(())
     }
+    fn update_adapter_from_info(&mut self, info: &AdapterInfo) {
+        self.addr = info.addr.clone();
+        self.addr_type = info.addr_type;
+        self.adapter_name = info.name.clone();
+        self.alias.replace(info.alias.clone());
+        self.class = info.class;
+        self.powered.set(info.powered);
+        self.discoverable.set(info.discoverable);
+        self.discoverable_timeout.set(info.discoverable_timeout);
+        self.pairable.set(info.pairable);
+        self.pairable_timeout.set(info.pairable_timeout);
+        self.discovering = info.discovering;
+        self.uuids = info.uuids.clone();
+        self.modalias = info.modalias.clone();
+        self.roles = info.roles.clone();
+    }
+    fn invalidate(&mut self, invalidated: Vec<String>) {
+        for prop in invalidated {
+            match prop.as_str() {
+                "Modalias" => self.modalias = None,
+                _ => (),
+            }
+        }
+    }
     /// Get the `MAC` of the local adapter.
     pub fn addr(&self) -> &MAC {
         &self.addr
     }
+    /// Get the address type of the local adapter.
+    pub fn addr_type(&self) -> AddrType {
+        self.addr_type
+    }
+    /// Get the system name of the local adapter.
+    pub fn adapter_name(&self) -> &str {
+        &self.adapter_name
+    }
+    /// Get the alias of the local adapter. Bluez defaults this to the system name.
+    pub fn alias(&self) -> String {
+        self.alias.borrow().clone()
+    }
+    /// Get the Bluetooth class of device of the local adapter.
+    pub fn class(&self) -> u32 {
+        self.class
+    }
+    /// Get whether the Bluez controller is currently pairable.
+    pub fn pairable(&self) -> bool {
+        self.pairable.get()
+    }
+    /// Get the timeout, in seconds, after which the controller stops being pairable.
+    /// Zero means the controller stays pairable indefinitely.
+    pub fn pairable_timeout(&self) -> u32 {
+        self.pairable_timeout.get()
+    }
+    /// Get the timeout, in seconds, after which the controller stops being discoverable.
+    /// Zero means the controller stays discoverable indefinitely.
+    pub fn discoverable_timeout(&self) -> u32 {
+        self.discoverable_timeout.get()
+    }
+    /// Get whether the Bluez controller is currently discovering devices.
+    pub fn discovering(&self) -> bool {
+        self.discovering
+    }
+    /// Get the UUIDs of the profiles available on the local adapter.
+    pub fn uuids(&self) -> &[UUID] {
+        &self.uuids
+    }
+    /// Get the local device ID information in modalias format.
+    pub fn modalias(&self) -> Option<&str> {
+        self.modalias.as_deref()
+    }
+    /// Get the roles supported by the local adapter, such as `"central"` or `"peripheral"`.
+    pub fn roles(&self) -> &[String] {
+        &self.roles
+    }
     /// Allows for changing which messages sources are allowed or rejected.
     ///
     /// By default, `Bluetooth`'s filter is set to org.bluez, only allowing
@@ -528,6 +742,30 @@ impl Bluetooth {
         self.services.insert(service.uuid.clone(), service);
         Ok(())
     }
+    /// Resolves the included services of each local service to their object paths.
+    fn resolve_includes(&mut self) -> Result<(), Error> {
+        let paths: HashMap<UUID, PathBuf> = self
+            .services
+            .iter()
+            .map(|(uuid, serv)| (uuid.clone(), serv.path.clone()))
+            .collect();
+        for serv in self.services.values_mut() {
+            let mut include_paths = Vec::with_capacity(serv.includes().len());
+            for inc in serv.includes() {
+                match paths.get(inc) {
+                    Some(path) => include_paths.push(path.clone()),
+                    None => {
+                        return Err(Error::BadInput(format!(
+                            "Service {} includes {}, which hasn't been added",
+                            serv.uuid, inc
+                        )))
+                    }
+                }
+            }
+            serv.include_paths = include_paths;
+        }
+        Ok(())
+    }
     /// Access a service that has been added to the `Bluetooth` instance.
     pub fn get_service<T: ToUUID>(&mut self, uuid: T) -> Option<LocalService<'_>> {
         let uuid = uuid.to_uuid();
@@ -721,61 +959,213 @@ impl Bluetooth {
         &mut self,
         on: bool,
     ) -> Result<Pending<Result<(), Error>, (Rc<Cell<bool>>, bool, &'static str)>, Error> {
+        let data = (self.powered.clone(), on, bluetooth_cb::POWER);
+        self.set_adapter_prop("Powered", on, data, &bluetooth_cb::set_power_cb)
+    }
+    /// Set the Bluez controller power on (`true`) or off and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn set_power_wait(&mut self, on: bool) -> Result<(), Error> {
+        let pend = self.set_power(on)?;
+        self.wait_result_variant(pend)
+    }
+    fn set_adapter_prop<T: Marshal + Signature, U: 'static>(
+        &mut self,
+        prop: &str,
+        val: T,
+        data: U,
+        cb: &'static dyn Fn(MarshalledMessage, U) -> Result<(), Error>,
+    ) -> Result<Pending<Result<(), Error>, U>, Error> {
         let mut msg = MessageBuilder::new()
             .call("Set".to_string())
             .on(self.blue_path.to_str().unwrap().to_string())
             .with_interface(PROP_IF_STR.to_string())
             .at(BLUEZ_DEST.to_string())
             .build();
-        msg.body.push_param2(ADAPTER_IF_STR, "Powered").unwrap();
-        msg.body.push_variant(on).unwrap();
+        msg.body.push_param2(ADAPTER_IF_STR, prop).unwrap();
+        msg.body.push_variant(val).unwrap();
         let dbus_res = self.rpc_con.send_message(&mut msg, Timeout::Infinite)?;
         Ok(Pending {
-            typ: Some(PendingType::MessageCb(&bluetooth_cb::set_power_cb)),
+            typ: Some(PendingType::MessageCb(cb)),
             dbus_res,
-            data: Some((self.powered.clone(), on, bluetooth_cb::POWER)),
+            data: Some(data),
             leaking: Rc::downgrade(&self.leaking),
         })
     }
-    /// Set the Bluez controller power on (`true`) or off and waits for the response.
+    /// Set whether the Bluez controller should be discoverable (`true`) or not.
+    pub fn set_discoverable(
+        &mut self,
+        on: bool,
+    ) -> Result<Pending<Result<(), Error>, (Rc<Cell<bool>>, bool, &'static str)>, Error> {
+        let data = (self.discoverable.clone(), on, bluetooth_cb::DISCOVERABLE);
+        self.set_adapter_prop("Discoverable", on, data, &bluetooth_cb::set_power_cb)
+    }
+    /// Get whether the Bluez controller is currently discoverable.
+    pub fn discoverable(&self) -> bool {
+        self.discoverable.get()
+    }
+    /// Set whether the Bluez controller should be discoverable (`true`) or not and waits for the response.
     ///
     /// **Calls process_requests()**
-    pub fn set_power_wait(&mut self, on: bool) -> Result<(), Error> {
+    pub fn set_discoverable_wait(&mut self, on: bool) -> Result<(), Error> {
         let pend = self.set_discoverable(on)?;
         self.wait_result_variant(pend)
     }
-    /// Set whether the Bluez controller should be discoverable (`true`) or not.
-    pub fn set_discoverable(
+    /// Set how long, in seconds, the Bluez controller stays discoverable after it is made discoverable.
+    /// Zero disables the timeout.
+    pub fn set_discoverable_timeout(
+        &mut self,
+        timeout: u32,
+    ) -> Result<Pending<Result<(), Error>, (Rc<Cell<u32>>, u32, &'static str)>, Error> {
+        let data = (
+            self.discoverable_timeout.clone(),
+            timeout,
+            bluetooth_cb::DISCOVERABLE_TIMEOUT,
+        );
+        self.set_adapter_prop(
+            "DiscoverableTimeout",
+            timeout,
+            data,
+            &bluetooth_cb::set_timeout_cb,
+        )
+    }
+    /// Set how long the Bluez controller stays discoverable and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn set_discoverable_timeout_wait(&mut self, timeout: u32) -> Result<(), Error> {
+        let pend = self.set_discoverable_timeout(timeout)?;
+        self.wait_result_variant(pend)
+    }
+    /// Set whether the Bluez controller should be pairable (`true`) or not.
+    pub fn set_pairable(
         &mut self,
         on: bool,
     ) -> Result<Pending<Result<(), Error>, (Rc<Cell<bool>>, bool, &'static str)>, Error> {
+        let data = (self.pairable.clone(), on, bluetooth_cb::PAIRABLE);
+        self.set_adapter_prop("Pairable", on, data, &bluetooth_cb::set_power_cb)
+    }
+    /// Set whether the Bluez controller should be pairable (`true`) or not and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn set_pairable_wait(&mut self, on: bool) -> Result<(), Error> {
+        let pend = self.set_pairable(on)?;
+        self.wait_result_variant(pend)
+    }
+    /// Set how long, in seconds, the Bluez controller stays pairable after it is made pairable.
+    /// Zero disables the timeout.
+    pub fn set_pairable_timeout(
+        &mut self,
+        timeout: u32,
+    ) -> Result<Pending<Result<(), Error>, (Rc<Cell<u32>>, u32, &'static str)>, Error> {
+        let data = (
+            self.pairable_timeout.clone(),
+            timeout,
+            bluetooth_cb::PAIRABLE_TIMEOUT,
+        );
+        self.set_adapter_prop(
+            "PairableTimeout",
+            timeout,
+            data,
+            &bluetooth_cb::set_timeout_cb,
+        )
+    }
+    /// Set how long the Bluez controller stays pairable and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn set_pairable_timeout_wait(&mut self, timeout: u32) -> Result<(), Error> {
+        let pend = self.set_pairable_timeout(timeout)?;
+        self.wait_result_variant(pend)
+    }
+    /// Set the alias of the local adapter. Setting an empty alias will restore
+    /// the default alias, the system name.
+    pub fn set_alias(
+        &mut self,
+        alias: &str,
+    ) -> Result<Pending<Result<(), Error>, (Rc<RefCell<String>>, String, &'static str)>, Error>
+    {
+        let data = (self.alias.clone(), alias.to_string(), bluetooth_cb::ALIAS);
+        self.set_adapter_prop("Alias", alias.to_string(), data, &bluetooth_cb::set_alias_cb)
+    }
+    /// Set the alias of the local adapter and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn set_alias_wait(&mut self, alias: &str) -> Result<(), Error> {
+        let pend = self.set_alias(alias)?;
+        self.wait_result_variant(pend)
+    }
+    fn adapter_call(
+        &mut self,
+        method: &str,
+        filter: Option<&DiscoveryFilter>,
+        err_str: &'static str,
+    ) -> Result<Pending<Result<(), Error>, &'static str>, Error> {
         let mut msg = MessageBuilder::new()
-            .call("Set".to_string())
+            .call(method.to_string())
             .on(self.blue_path.to_str().unwrap().to_string())
-            .with_interface(PROP_IF_STR.to_string())
+            .with_interface(ADAPTER_IF_STR.to_string())
             .at(BLUEZ_DEST.to_string())
             .build();
-        msg.body
-            .push_param2(ADAPTER_IF_STR, "Discoverable")
-            .unwrap();
-        msg.body.push_variant(on).unwrap();
+        if let Some(filter) = filter {
+            msg.body
+                .push_old_param(&Param::Container(Container::Dict(filter.to_dict())))
+                .unwrap();
+        }
         let dbus_res = self.rpc_con.send_message(&mut msg, Timeout::Infinite)?;
         Ok(Pending {
-            typ: Some(PendingType::MessageCb(&bluetooth_cb::set_power_cb)),
+            typ: Some(PendingType::MessageCb(&bluetooth_cb::method_cb)),
             dbus_res,
-            data: Some((self.discoverable.clone(), on, bluetooth_cb::DISCOVERABLE)),
+            data: Some(err_str),
             leaking: Rc::downgrade(&self.leaking),
         })
     }
-    /// Get whether the Bluez controller is currently discoverable.
-    pub fn discoverable(&self) -> bool {
-        self.discoverable.get()
+    /// Set the filter used by the Bluez controller during device discovery.
+    /// The filter is applied to the next, or current, discovery session started with [`start_discovery()`].
+    ///
+    /// [`start_discovery()`]: ./struct.Bluetooth.html#method.start_discovery
+    pub fn set_discovery_filter(
+        &mut self,
+        filter: &DiscoveryFilter,
+    ) -> Result<Pending<Result<(), Error>, &'static str>, Error> {
+        filter.validate()?;
+        self.adapter_call(
+            "SetDiscoveryFilter",
+            Some(filter),
+            bluetooth_cb::DISCOVERY_FILTER,
+        )
     }
-    /// Set whether the Bluez controller should be discoverable (`true`) or not and waits for the response.
+    /// Set the filter used by the Bluez controller during device discovery and waits for the response.
     ///
     /// **Calls process_requests()**
-    pub fn set_discoverable_wait(&mut self, on: bool) -> Result<(), Error> {
-        let pend = self.set_discoverable(on)?;
+    pub fn set_discovery_filter_wait(&mut self, filter: &DiscoveryFilter) -> Result<(), Error> {
+        let pend = self.set_discovery_filter(filter)?;
+        self.wait_result_variant(pend)
+    }
+    /// Start device discovery on the Bluez controller.
+    ///
+    /// Devices found during discovery are added to the known devices, as Bluez reports them,
+    /// while [`process_requests()`] is called. They can be listed with [`devices()`].
+    ///
+    /// [`process_requests()`]: ./struct.Bluetooth.html#method.process_requests
+    /// [`devices()`]: ./struct.Bluetooth.html#method.devices
+    pub fn start_discovery(&mut self) -> Result<Pending<Result<(), Error>, &'static str>, Error> {
+        self.adapter_call("StartDiscovery", None, bluetooth_cb::START_DISCOVERY)
+    }
+    /// Start device discovery on the Bluez controller and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn start_discovery_wait(&mut self) -> Result<(), Error> {
+        let pend = self.start_discovery()?;
+        self.wait_result_variant(pend)
+    }
+    /// Stop device discovery on the Bluez controller.
+    pub fn stop_discovery(&mut self) -> Result<Pending<Result<(), Error>, &'static str>, Error> {
+        self.adapter_call("StopDiscovery", None, bluetooth_cb::STOP_DISCOVERY)
+    }
+    /// Stop device discovery on the Bluez controller and waits for the response.
+    ///
+    /// **Calls process_requests()**
+    pub fn stop_discovery_wait(&mut self) -> Result<(), Error> {
+        let pend = self.stop_discovery()?;
         self.wait_result_variant(pend)
     }
     fn wait_result_variant<O, U>(
@@ -796,6 +1186,7 @@ impl Bluetooth {
     ///
     /// **Calls process_requests()**
     pub fn register_application(&mut self) -> Result<(), Error> {
+        self.resolve_includes()?;
         let path = self.get_path();
         let empty_dict = HashMap::new();
         let dict = params::Dict {
@@ -857,20 +1248,72 @@ impl Bluetooth {
                     if self.verbose >= 1 {
                         eprintln!("Registered application with bluez.");
                     };
+                    self.registered = true;
                     Ok(())
                 };
             }
         }
     }
-    /// **Unimplemented**
-    ///
     /// Unregisters the local GATT services from the Bluez controller.
     ///
+    /// On success, any notify or write sockets acquired by remote devices
+    /// for local characteristics are closed. The services remain added to the `Bluetooth`
+    /// instance, so they can be modified and registered again with [`register_application()`].
+    ///
     /// **Calls process_requests()**
+    ///
+    /// [`register_application()`]: ./struct.Bluetooth.html#method.register_application
     pub fn unregister_application(&mut self) -> Result<(), Error> {
-        unimplemented!();
-        self.registered = false;
-        Ok(())
+        let path = self.get_path().to_str().unwrap().to_string();
+        let mut msg = MessageBuilder::new()
+            .call(UNREGISTER_CALL.to_string())
+            .with_interface(MANAGER_IF_STR.to_string())
+            .on(self.blue_path.to_str().unwrap().to_string())
+            .at(BLUEZ_DEST.to_string())
+            .build();
+        msg.body
+            .push_old_param(&Param::Base(Base::ObjectPath(path)))
+            .unwrap();
+        let msg_idx = self.rpc_con.send_message(&mut msg, Timeout::Infinite)?;
+        loop {
+            self.process_requests()?;
+            if let Some(res) = self.rpc_con.try_get_response(msg_idx) {
+                return match res.typ {
+                    MessageType::Reply => {
+                        if self.verbose >= 1 {
+                            eprintln!("Unregistered application with bluez.");
+                        }
+                        self.registered = false;
+                        for serv in self.services.values_mut() {
+                            for character in serv.chars.values_mut() {
+                                character.close_fds();
+                            }
+                        }
+                        Ok(())
+                    }
+                    MessageType::Error => {
+                        let res = res.unmarshall_all().unwrap();
+                        let err_str = match res.params.get(0) {
+                            Some(Param::Base(Base::String(err))) => format!(
+                                "Failed to unregister application with bluez: {}: {:?}",
+                                res.dynheader.error_name.unwrap(),
+                                err
+                            ),
+                            _ => format!(
+                                "Failed to unregister application with bluez: {}",
+                                res.dynheader.error_name.unwrap()
+                            ),
+                        };
+                        Err(Error::Bluez(err_str))
+                    }
+                    _ => unreachable!(),
+                };
+            }
+        }
+    }
+    /// Checks if the local application's GATT services are currently registered with Bluez.
+    pub fn registered(&self) -> bool {
+        self.registered
     }
 
     fn check_incoming(&self, sender: &str) -> bool {
@@ -881,6 +1324,99 @@ impl Bluetooth {
             None => true,
         }
     }
+    /// Replies to a read of a local characteristic or descriptor that was deferred
+    /// by a [`ValOrFn::Deferred`] function.
+    ///
+    /// `res` should contain the entire value of the attribute; the offset of the read is
+    /// applied to it before replying. If Bluez has already timed out the read, `Error::Timeout` is returned.
+    ///
+    /// [`ValOrFn::Deferred`]: ./gatt/enum.ValOrFn.html#variant.Deferred
+    pub fn reply_read(
+        &mut self,
+        reply: ReadReply,
+        res: Result<AttValue, AttError>,
+    ) -> Result<(), Error> {
+        if reply.expired() {
+            return Err(Error::Timeout);
+        }
+        let mut msg = match res {
+            Ok(val) => {
+                let start = reply.ctx.offset as usize;
+                if start > val.len() {
+                    AttError::InvalidOffset.to_response(&reply.call)
+                } else {
+                    let mut msg = reply.call.make_response();
+                    msg.body.push_param(&val[start..]).unwrap();
+                    msg
+                }
+            }
+            Err(err) => err.to_response(&reply.call),
+        };
+        self.rpc_con.send_message(&mut msg, Timeout::Infinite)?;
+        Ok(())
+    }
+    /// Replies to a write of a local characteristic or descriptor that was deferred
+    /// by its `deferred_write` callback.
+    ///
+    /// On success, giving a `Some` variant will overwrite the value of the attribute,
+    /// while `None` leaves the value the same as it was before the write.
+    /// If Bluez has already timed out the write, `Error::Timeout` is returned and the value is not changed.
+    pub fn reply_write(
+        &mut self,
+        reply: WriteReply,
+        res: Result<Option<ValOrFn>, AttError>,
+    ) -> Result<(), Error> {
+        if reply.expired() {
+            return Err(Error::Timeout);
+        }
+        let mut msg = match res {
+            Ok(vf) => {
+                if let Some(vf) = vf {
+                    let target = &reply.target;
+                    let char_base = self
+                        .services
+                        .get_mut(&target.serv_uuid)
+                        .and_then(|serv_base| serv_base.chars.get_mut(&target.char_uuid));
+                    match (char_base, &target.desc_uuid) {
+                        (Some(char_base), Some(desc_uuid)) => {
+                            if let Some(desc_base) = char_base.descs.get_mut(desc_uuid) {
+                                desc_base.vf = vf;
+                            }
+                        }
+                        (Some(char_base), None) => char_base.set_vf(vf),
+                        (None, _) => (),
+                    }
+                }
+                reply.call.map(|call| call.make_response())
+            }
+            Err(err) => reply.call.map(|call| err.to_response(&call)),
+        };
+        // Reliable writes have already been responded to by Bluez.
+        if let Some(msg) = &mut msg {
+            self.rpc_con.send_message(msg, Timeout::Infinite)?;
+        }
+        Ok(())
+    }
+    /// Finishes the reliable writes to local characteristics that Bluez is done forwarding,
+    /// times out unconfirmed indications, and checks if any of the notify sockets have been closed.
+    fn check_local_chars(&mut self) -> Result<(), Error> {
+        let mut chars = Vec::new();
+        for (serv_uuid, serv_base) in &mut self.services {
+            for (char_uuid, char_base) in &mut serv_base.chars {
+                char_base.check_notify_fd();
+                if char_base.has_reliable_writes() || char_base.has_indications() {
+                    chars.push((serv_uuid.clone(), char_uuid.clone()));
+                }
+            }
+        }
+        for (serv_uuid, char_uuid) in chars {
+            let mut serv = LocalService::new(self, serv_uuid);
+            let mut character = LocalChar::new(&mut serv, char_uuid);
+            character.check_reliable_writes()?;
+            character.check_indications();
+        }
+        Ok(())
+    }
     /// Process incoming DBus requests for the local application.
     ///
     /// When using `Bluetooth` this function should be called on a regular basis.
@@ -943,7 +1479,11 @@ impl Bluetooth {
                                                 LocalChar::new(&mut serv, char_uuid);
                                             let mut desc =
                                                 LocalDesc::new(&mut character, desc_uuid);
-                                            desc.desc_call(call)
+                                            match desc.desc_call(call) {
+                                                Some(reply) => reply,
+                                                // The reply was deferred by the descriptor's handler
+                                                None => continue,
+                                            }
                                         }
                                         INTRO_IF_STR => desc_base.introspectable(call),
                                         _ => standard_messages::unknown_method(&call.dynheader),
@@ -957,7 +1497,11 @@ impl Bluetooth {
                                             let mut serv = LocalService::new(self, serv_uuid);
                                             let mut character =
                                                 LocalChar::new(&mut serv, char_uuid);
-                                            character.char_call(call)
+                                            match character.char_call(call) {
+                                                Some(reply) => reply,
+                                                // The reply was deferred by the characteristic's handler
+                                                None => continue,
+                                            }
                                         }
                                         INTRO_IF_STR => char_base.introspectable(call),
                                         _ => standard_messages::unknown_method(&call.dynheader),
@@ -983,6 +1527,8 @@ impl Bluetooth {
                         LEAD_IF_STR => match call.dynheader.member.as_ref().unwrap().as_str() {
                             "Release" => {
                                 adv.active = false;
+                                self.events
+                                    .push_back(BluetoothEvent::AdvertisementReleased(adv.index));
                                 call.dynheader.make_response()
                             }
                             _ => standard_messages::unknown_method(&call.dynheader),
@@ -1005,6 +1551,7 @@ impl Bluetooth {
                 close(fd).ok();
             }
         }
+        self.check_local_chars()?;
         while let Some(sig) = self.rpc_con.try_get_signal() {
             match sig.dynheader.interface.as_ref().unwrap().as_str() {
                 OBJ_MANAGER_IF_STR => {
@@ -1033,6 +1580,10 @@ impl Bluetooth {
                                             self.filter_dest.as_ref().unwrap().0
                                         );
                                     }
+                                    for mac in self.devices.keys() {
+                                        self.events
+                                            .push_back(BluetoothEvent::DeviceRemoved(mac.clone()));
+                                    }
                                     self.clear_devices();
                                 }
                             }
@@ -1056,7 +1607,8 @@ impl Bluetooth {
         Ok(())
     }
     fn properties_changed(&mut self, sig: MarshalledMessage) -> Result<(), Error> {
-        if let Some(child) = self.match_remote(&sig.dynheader) {
+        let path: &Path = sig.dynheader.object.as_ref().unwrap().as_ref();
+        if let Some(child) = self.match_remote(path) {
             let mut parser = sig.body.parser();
             let interface: &str = parser.get()?;
             let changed = parser.get()?;
@@ -1068,12 +1620,29 @@ impl Bluetooth {
                             let serv = dev.get_child(&serv_uuid).unwrap();
                             match child {
                                 Some((char_uuid, child)) => {
-                                    let mut character = serv.get_child(&char_uuid).unwrap();
+                                    let character = serv.get_child(&char_uuid).unwrap();
                                     match child {
-                                        Some(desc_uuid) => unimplemented!(),
+                                        Some(desc_uuid) => {
+                                            if interface == DESC_IF_STR {
+                                                character
+                                                    .descs
+                                                    .get_mut(&desc_uuid)
+                                                    .unwrap()
+                                                    .update_from_changed(changed)?;
+                                            }
+                                        }
                                         None => {
                                             if interface == CHAR_IF_STR {
+                                                let value_changed = changed.contains_key(VALUE_PROP);
                                                 character.update_from_changed(changed)?;
+                                                if value_changed {
+                                                    character.call_value_cb();
+                                                    self.events.push_back(
+                                                        BluetoothEvent::CharacteristicValueChanged(
+                                                            dev_mac, serv_uuid, char_uuid,
+                                                        ),
+                                                    );
+                                                }
                                             }
                                         }
                                     }
@@ -1087,14 +1656,24 @@ impl Bluetooth {
                         }
                         None => {
                             if interface == DEV_IF_STR {
-                                dev.update_from_changed(changed)?;
+                                let invalidated = parser.get()?;
+                                dev.update_from_changed(changed, &mut self.events)?;
+                                dev.invalidate(invalidated);
                             }
                         }
                     }
                 }
                 None => {
                     if interface == ADAPTER_IF_STR {
+                        let invalidated = parser.get()?;
+                        let powered_changed = changed.contains_key("Powered");
                         self.update_from_changed(changed)?;
+                        if powered_changed {
+                            self.events.push_back(BluetoothEvent::AdapterPoweredChanged(
+                                self.powered.get(),
+                            ));
+                        }
+                        self.invalidate(invalidated);
                     }
                 }
             }
@@ -1118,30 +1697,191 @@ impl Bluetooth {
         */
     }
     fn interface_added(&mut self, sig: MarshalledMessage) -> Result<(), Error> {
-        match self.match_remote(&sig.dynheader) {
-            Some(Some((mac, Some((serv_uuid, child_uuid))))) => {
+        let mut parser = sig.body.parser();
+        let path: path::ObjectPathBuf = parser.get()?;
+        let mut i_and_p: HashMap<String, HashMap<String, Variant>> = parser.get()?;
+        if path.parent() == Some(ADAPTER_ROOT.as_ref()) {
+            if let Some(props) = i_and_p.remove(ADAPTER_IF_STR) {
+                let info = AdapterInfo::from_props(props, path.into())?;
+                if info.path.as_path() == self.blue_path.as_ref() {
+                    self.update_adapter_from_info(&info);
+                }
+                if self.verbose >= 1 {
+                    eprintln!("Adapter added: {} ({:?})", info.addr, info.path);
+                }
+                self.events
+                    .push_back(BluetoothEvent::Adapter(AdapterEvent::Added(info)));
+            }
+            return Ok(());
+        }
+        match self.match_remote(&path) {
+            // the object is already known so its properties are updated
+            Some(Some((mac, child_uuid))) => {
                 let dev = self.devices.get_mut(&mac).unwrap();
-                let serv = dev.services.get_mut(&serv_uuid).unwrap();
                 match child_uuid {
-                    Some((char_uuid, child_uuid)) => unimplemented!(),
+                    Some((serv_uuid, child_uuid)) => {
+                        let serv = dev.services.get_mut(&serv_uuid).unwrap();
+                        match child_uuid {
+                            Some((char_uuid, desc_uuid)) => {
+                                let character = serv.chars.get_mut(&char_uuid).unwrap();
+                                match desc_uuid {
+                                    Some(desc_uuid) => match i_and_p.remove(DESC_IF_STR) {
+                                        Some(props) => character
+                                            .descs
+                                            .get_mut(&desc_uuid)
+                                            .unwrap()
+                                            .update_from_changed(props),
+                                        None => Ok(()),
+                                    },
+                                    None => match i_and_p.remove(CHAR_IF_STR) {
+                                        Some(props) => character.update_from_changed(props),
+                                        None => Ok(()),
+                                    },
+                                }
+                            }
+                            None => match i_and_p.remove(SERV_IF_STR) {
+                                Some(props) => serv.update_all(props),
+                                None => Ok(()),
+                            },
+                        }
+                    }
+                    None => match i_and_p.remove(DEV_IF_STR) {
+                        Some(props) => dev.update_from_changed(props, &mut self.events),
+                        None => Ok(()),
+                    },
+                }
+            }
+            Some(None) => Ok(()),
+            None => {
+                let parent = match path.parent() {
+                    Some(parent) => parent,
+                    None => return Ok(()),
+                };
+                if parent == self.blue_path.as_ref() {
+                    // check if a new device was found by the adapter
+                    if let Some(dev_props) = i_and_p.remove(DEV_IF_STR) {
+                        let dev = RemoteDeviceBase::from_props(dev_props, path.into())?;
+                        if self.verbose >= 1 {
+                            eprintln!("Discovered device: {}", dev.mac);
+                        }
+                        self.events
+                            .push_back(BluetoothEvent::DeviceDiscovered(dev.mac.clone()));
+                        self.insert_device(dev);
+                    }
+                    return Ok(());
+                }
+                // check if a new attribute was added to a known remote object
+                let (mac, child_uuid) = match self.match_remote(parent) {
+                    Some(Some(matc)) => matc,
+                    _ => return Ok(()),
+                };
+                let dev = self.devices.get_mut(&mac).unwrap();
+                match child_uuid {
+                    Some((serv_uuid, Some((char_uuid, None)))) => {
+                        if let Some(props) = i_and_p.remove(DESC_IF_STR) {
+                            let desc = RemoteDescBase::from_props(props, path.into())?;
+                            let serv = dev.services.get_mut(&serv_uuid).unwrap();
+                            let character = serv.chars.get_mut(&char_uuid).unwrap();
+                            character.descs.insert(desc.uuid().clone(), desc);
+                        }
+                    }
+                    Some((serv_uuid, None)) => {
+                        if let Some(props) = i_and_p.remove(CHAR_IF_STR) {
+                            let character = RemoteCharBase::from_props(props, path.into())?;
+                            let serv = dev.services.get_mut(&serv_uuid).unwrap();
+                            serv.chars.insert(character.uuid().clone(), character);
+                        }
+                    }
                     None => {
-                        let mut i_and_p: HashMap<String, HashMap<String, Variant>> =
-                            sig.body.parser().get()?;
-                        match i_and_p.remove("org.bluez.GattService1") {
-                            Some(props) => serv.update_all(props),
-                            None => Ok(()),
+                        if let Some(props) = i_and_p.remove(SERV_IF_STR) {
+                            let serv = RemoteServiceBase::from_props(props, path.into())?;
+                            dev.services.insert(serv.uuid().clone(), serv);
                         }
                     }
+                    // descriptors have no children
+                    Some((_, Some((_, Some(_))))) => (),
                 }
+                Ok(())
             }
-            _ => Ok(()),
         }
     }
     fn interface_removed(&mut self, sig: MarshalledMessage) -> Result<(), Error> {
-        match self.match_remote(&sig.dynheader) {
+        let (path, interfaces): (path::ObjectPathBuf, Vec<String>) = sig.body.parser().get2()?;
+        if path.parent() == Some(ADAPTER_ROOT.as_ref()) {
+            if interfaces.iter().any(|i| i == ADAPTER_IF_STR) {
+                let path: PathBuf = path.into();
+                if path.as_path() == self.blue_path.as_ref() {
+                    // the adapter is gone so none of its state is valid anymore
+                    self.powered.set(false);
+                    self.discoverable.set(false);
+                    self.discovering = false;
+                    for mac in self.devices.keys() {
+                        self.events
+                            .push_back(BluetoothEvent::DeviceRemoved(mac.clone()));
+                    }
+                    self.devices.clear();
+                    self.comp_map.clear();
+                }
+                if self.verbose >= 1 {
+                    eprintln!("Adapter removed: {:?}", path);
+                }
+                self.events
+                    .push_back(BluetoothEvent::Adapter(AdapterEvent::Removed(path)));
+            }
+            return Ok(());
+        }
+        let has_if = |if_str: &str| interfaces.iter().any(|i| i == if_str);
+        // Dropping the removed bases closes any acquired fds
+        match self.match_remote(&path) {
             Some(Some((mac, child_uuid))) => match child_uuid {
-                Some((serv_uuid, child_uuid)) => unimplemented!(),
-                None => unimplemented!(),
+                Some((serv_uuid, child_uuid)) => {
+                    let dev = self.devices.get_mut(&mac).unwrap();
+                    let event = match child_uuid {
+                        Some((char_uuid, desc_uuid)) => {
+                            let serv = dev.services.get_mut(&serv_uuid).unwrap();
+                            match desc_uuid {
+                                Some(desc_uuid) => {
+                                    if !has_if(DESC_IF_STR) {
+                                        return Ok(());
+                                    }
+                                    let character = serv.chars.get_mut(&char_uuid).unwrap();
+                                    character.descs.remove(&desc_uuid);
+                                    BluetoothEvent::DescriptorRemoved(
+                                        mac, serv_uuid, char_uuid, desc_uuid,
+                                    )
+                                }
+                                None => {
+                                    if !has_if(CHAR_IF_STR) {
+                                        return Ok(());
+                                    }
+                                    serv.chars.remove(&char_uuid);
+                                    BluetoothEvent::CharacteristicRemoved(mac, serv_uuid, char_uuid)
+                                }
+                            }
+                        }
+                        None => {
+                            if !has_if(SERV_IF_STR) {
+                                return Ok(());
+                            }
+                            dev.services.remove(&serv_uuid);
+                            BluetoothEvent::ServiceRemoved(mac, serv_uuid)
+                        }
+                    };
+                    if self.verbose >= 1 {
+                        eprintln!("Remote attribute removed: {:?}", event);
+                    }
+                    self.events.push_back(event);
+                    Ok(())
+                }
+                None => {
+                    if has_if(DEV_IF_STR) {
+                        if self.verbose >= 1 {
+                            eprintln!("Device removed: {}", mac);
+                        }
+                        self.remove_device_base(&mac);
+                    }
+                    Ok(())
+                }
             },
             Some(None) | None => Ok(()),
         }
@@ -1176,9 +1916,8 @@ impl Bluetooth {
     }
     fn match_remote(
         &mut self,
-        header: &DynamicHeader,
+        path: &Path,
     ) -> Option<Option<(MAC, Option<(UUID, Option<(UUID, Option<UUID>)>)>)>> {
-        let path: &Path = header.object.as_ref().unwrap().as_ref();
         let path = match path.strip_prefix(self.blue_path.as_ref()) {
             Ok(p) => p,
             Err(_) => return None,
@@ -1190,7 +1929,7 @@ impl Bluetooth {
         };
         let mac = devmac_to_mac(first_comp)?;
         let dev = self.devices.get_mut(&mac)?;
-        let uuids = dev.match_dev(path.strip_prefix(&first_comp).unwrap())?;
+        let uuids = dev.match_dev(path)?;
         Some(Some((mac, uuids)))
     }
     fn match_services(&mut self, path: &Path) -> Option<(UUID, Option<(UUID, Option<UUID>)>)> {
@@ -1221,57 +1960,30 @@ impl Bluetooth {
     /// Clear the known the devices from the local application.
     /// This function does *not* remove the devices from the controller.
     /// It merely causes the local application to forget them.
+    /// Use [`remove_device()`] to remove a device from the controller.
+    ///
+    /// [`remove_device()`]: ./struct.Bluetooth.html#method.remove_device
     pub fn clear_devices(&mut self) {
-        self.devices.clear()
+        self.devices.clear();
+        self.comp_map.clear();
     }
     /// Used to get devices devices known to Bluez. This function does *not* trigger scan/discovery
-    /// on the Bluez controller. Use `set_scan()` to initiate actual device discovery.
+    /// on the Bluez controller. Use [`start_discovery()`] to initiate actual device discovery.
     ///
     /// **Calls process_requests()**
+    ///
+    /// [`start_discovery()`]: ./struct.Bluetooth.html#method.start_discovery
     pub fn discover_devices(&mut self) -> Result<Vec<MAC>, Error> {
         self.discover_devices_filter(self.blue_path.clone())
     }
 
-    /*
-    /// **Unimplemented**
-    pub fn set_scan(&mut self, on: bool) -> Result<(), Error> {
-        let mut msg = MessageBuilder::new()
-            .call("Set".to_string())
-            .on(self.blue_path.to_str().unwrap().to_string())
-            .with_interface(PROP_IF_STR.to_string())
-            .at(BLUEZ_DEST.to_string())
-            .build();
-        msg.body.push_param2(ADAPTER_IF_STR, "Scan").unwrap();
-        let variant = Param::Container(Container::Variant(Box::new(params::Variant {
-            sig: rustbus::signature::Type::Base(rustbus::signature::Base::Boolean),
-            value: Param::Base(Base::Boolean(on)),
-        })));
-        msg.body.push_old_param(&variant).unwrap();
-        let res_idx = self.rpc_con.send_message(&mut msg, Timeout::Infinite)?;
-        loop {
-            self.process_requests()?;
-            if let Some(res) = self.rpc_con.try_get_response(res_idx) {
-                match res.typ {
-                    MessageType::Reply => return Ok(()),
-                    MessageType::Error => {
-                        return Err(Error::DbusReqErr(format!(
-                            "Set power call failed: {:?}",
-                            res
-                        )))
-                    }
-                    _ => unreachable!(),
-                }
-            }
-        }
-    }
-    */
     /// Tries to resolve a `Pending`. If the `Pending` is not ready, or an
     /// error occurs it will return an `Err(ResolveError)`
     ///
     /// **Sometimes Calls process_requests()**. If the `Pending` is fetching remote information
     /// then this will call [`process_requests()`], but some `Pending` are already resolved when created,
     /// such as reading a local characteristic. (These "PreResolved" `Pending`s exist to satisfy trait defitions.
-    pub fn try_resolve<T, V, U>(
+    pub fn try_resolve<T, U>(
         &mut self,
         mut pend: Pending<T, U>,
     ) -> Result<T, ResolveError<T, U>> {
@@ -1323,7 +2035,7 @@ impl Bluetooth {
         &mut self,
         filter_path: T,
     ) -> Result<Vec<MAC>, Error> {
-        self.devices.clear();
+        self.clear_devices();
         let mut msg = MessageBuilder::new()
             .call(MANGAGED_OBJ_CALL.to_string())
             .at(BLUEZ_DEST.to_string())
@@ -1439,7 +2151,8 @@ impl Bluetooth {
                             .services
                             .insert(serv_base.uuid().clone(), serv_base);
                     }
-                    self.devices.insert(dev_base.uuid().clone(), dev_base);
+                    ret.push(dev_base.uuid().clone());
+                    self.insert_device(dev_base);
                 }
                 return Ok(ret);
             }
@@ -1451,6 +2164,62 @@ impl Bluetooth {
         self.devices.insert(devmac.clone(), device);
         self.comp_map.insert(comp, devmac);
     }
+    fn remove_device_base(&mut self, mac: &MAC) -> Option<RemoteDeviceBase> {
+        let device = self.devices.remove(mac)?;
+        self.comp_map.remove(device.path.file_name().unwrap());
+        self.events
+            .push_back(BluetoothEvent::DeviceRemoved(mac.clone()));
+        Some(device)
+    }
+    /// Removes the device, and any bonding information, from the Bluez controller.
+    ///
+    /// Unlike [`clear_devices()`], this removes the device from Bluez itself.
+    /// If the device is connected, it will be disconnected first by Bluez.
+    /// The device is removed from the locally known devices once Bluez signals
+    /// its removal, while [`process_requests()`] is called.
+    ///
+    /// [`clear_devices()`]: ./struct.Bluetooth.html#method.clear_devices
+    /// [`process_requests()`]: ./struct.Bluetooth.html#method.process_requests
+    pub fn remove_device(
+        &mut self,
+        mac: &MAC,
+    ) -> Result<Pending<Result<(), Error>, &'static str>, Error> {
+        let path = match self.devices.get(mac) {
+            Some(dev) => dev.path.clone(),
+            None => match mac_to_devmac(mac) {
+                Some(devmac) => self.blue_path.join(devmac),
+                None => return Err(Error::BadInput("Invalid mac was given".to_string())),
+            },
+        };
+        let mut msg = MessageBuilder::new()
+            .call("RemoveDevice".to_string())
+            .on(self.blue_path.to_str().unwrap().to_string())
+            .with_interface(ADAPTER_IF_STR.to_string())
+            .at(BLUEZ_DEST.to_string())
+            .build();
+        msg.body
+            .push_old_param(&Param::Base(Base::ObjectPath(
+                path.to_str().unwrap().to_string(),
+            )))
+            .unwrap();
+        let dbus_res = self.rpc_con.send_message(&mut msg, Timeout::Infinite)?;
+        Ok(Pending {
+            typ: Some(PendingType::MessageCb(&bluetooth_cb::method_cb)),
+            dbus_res,
+            data: Some(bluetooth_cb::REMOVE_DEVICE),
+            leaking: Rc::downgrade(&self.leaking),
+        })
+    }
+    /// Removes the device from the Bluez controller and waits for the response.
+    /// On success the device is removed from the locally known devices.
+    ///
+    /// **Calls process_requests()**
+    pub fn remove_device_wait(&mut self, mac: &MAC) -> Result<(), Error> {
+        let pend = self.remove_device(mac)?;
+        self.wait_result_variant(pend)?;
+        self.remove_device_base(mac);
+        Ok(())
+    }
     /// Get a device from the Bluez controller.
     ///
     /// **Calls process_requests()**
@@ -1786,6 +2555,10 @@ fn base_param_to_variant(b: Base) -> Param {
             sig: signature::Type::Base(signature::Base::Uint16),
             value: Param::Base(u.into()),
         },
+        Base::Int16(i) => params::Variant {
+            sig: signature::Type::Base(signature::Base::Int16),
+            value: Param::Base(i.into()),
+        },
         Base::ObjectPath(p) => params::Variant {
             sig: signature::Type::Base(signature::Base::ObjectPath),
             value: Param::Base(Base::ObjectPath(p)),
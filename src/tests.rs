@@ -1,4 +1,4 @@
-use crate::validate_uuid;
+use crate::{validate_uuid, DiscoveryFilter, ToUUID};
 
 #[test]
 pub fn test_val_uuid() {
@@ -13,3 +13,29 @@ pub fn test_val_uuid() {
     assert!(!validate_uuid("8h33385h-4465-47hh-a25h-3631fh1h4861")); // not hex
     assert!(!validate_uuid("-h33385h-4465-47hh-a25h-3631fh1h4861")); // first number is negative
 }
+
+#[test]
+pub fn test_discovery_filter_validate() {
+    let mut filter = DiscoveryFilter::new();
+    assert!(filter.validate().is_ok());
+    filter
+        .uuids
+        .push("00001104-0000-1000-8000-00805f9b34fb".to_uuid());
+    filter.rssi = Some(-70);
+    assert!(filter.validate().is_ok());
+    filter.pathloss = Some(40); // rssi and pathloss are mutually exclusive
+    assert!(filter.validate().is_err());
+    filter.rssi = None;
+    filter.uuids.push("00001104-0000-1000-8000-00805f9b34f".into()); // too short
+    assert!(filter.validate().is_err());
+}
+
+#[test]
+pub fn test_discovery_filter_default_dict() {
+    // Keys left at their defaults are not sent to Bluez.
+    let mut filter = DiscoveryFilter::new();
+    assert!(filter.to_dict().map.is_empty());
+    filter.duplicate_data = false;
+    filter.discoverable = true;
+    assert_eq!(filter.to_dict().map.len(), 2);
+}